@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+
+/// Typos are never forgiven in answers shorter than this, so "it" can't become "at".
+const MIN_TYPO_LENGTH: usize = 4;
+
+const ARTICLES: [&str; 3] = ["a", "an", "the"];
+
+const NUMBER_WORDS: [&str; 21] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+];
+
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// How forgiving a riddle pack is about the way an answer is written.
+///
+/// The default accepts "A Piano!" for "piano" and "three ducks" for "3", but no typos, since one
+/// letter out often makes a different word ("corn" for "coin").
+/// Turning everything off gives exact matching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnswerMatching {
+    pub ignore_case: bool,
+    /// Drops "a", "an" and "the".
+    pub ignore_articles: bool,
+    pub ignore_punctuation: bool,
+    /// Treats "three" and "3" as the same, and "twenty-one" and "21", for numbers below a hundred.
+    pub number_words: bool,
+    /// Also tries the answer with any words from the question taken out, so "three ducks" counts as "three".
+    pub ignore_question_words: bool,
+    /// How many single-letter mistakes (edit distance) are forgiven.
+    pub max_typos: usize,
+}
+
+impl Default for AnswerMatching {
+    fn default() -> AnswerMatching {
+        AnswerMatching {
+            ignore_case: true,
+            ignore_articles: true,
+            ignore_punctuation: true,
+            number_words: true,
+            ignore_question_words: true,
+            max_typos: 0,
+        }
+    }
+}
+
+impl AnswerMatching {
+    pub fn exact() -> AnswerMatching {
+        AnswerMatching {
+            ignore_case: false,
+            ignore_articles: false,
+            ignore_punctuation: false,
+            number_words: false,
+            ignore_question_words: false,
+            max_typos: 0,
+        }
+    }
+
+    /// Whether `input` counts as any of `answers` to `question`.
+    pub fn accepts(&self, question: &str, answers: &[String], input: &str) -> bool {
+        let mut attempts = vec![self.normalise(input)];
+
+        if self.ignore_question_words {
+            let question_words = self.words(question);
+
+            let without = self
+                .words(input)
+                .into_iter()
+                .filter(|word| !question_words.contains(word))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            if !without.is_empty() {
+                attempts.push(without);
+            }
+        }
+
+        answers.iter().any(|answer| {
+            let answer = self.normalise(answer);
+
+            attempts
+                .iter()
+                .any(|attempt| self.close_enough(&answer, attempt))
+        })
+    }
+
+    pub fn normalise(&self, text: &str) -> String {
+        self.words(text).join(" ")
+    }
+
+    fn words(&self, text: &str) -> Vec<String> {
+        let mut text = text.to_string();
+
+        if self.ignore_case {
+            text = text.to_lowercase();
+        }
+
+        if self.ignore_punctuation {
+            text = text
+                .chars()
+                .filter(|c| *c != '\'')
+                .map(|c| {
+                    if c.is_alphanumeric() || c.is_whitespace() {
+                        c
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+        }
+
+        let mut words = text
+            .split_whitespace()
+            .filter(|word| !(self.ignore_articles && ARTICLES.contains(word)))
+            .peekable();
+
+        let mut found = vec![];
+
+        while let Some(word) = words.next() {
+            let Some(mut number) = Some(word).filter(|_| self.number_words).and_then(number) else {
+                found.push(word.to_string());
+                continue;
+            };
+
+            // "twenty one", once punctuation has taken the hyphen out
+            if number >= 20 && number % 10 == 0 {
+                if let Some(unit) = words.peek().and_then(|next| number_below_ten(next)) {
+                    words.next();
+                    number += unit;
+                }
+            }
+
+            found.push(number.to_string());
+        }
+
+        found
+    }
+
+    fn close_enough(&self, answer: &str, attempt: &str) -> bool {
+        if answer == attempt {
+            return true;
+        }
+
+        answer.chars().count() >= MIN_TYPO_LENGTH
+            && edit_distance(answer, attempt) <= self.max_typos
+    }
+}
+
+/// The number a word like "three", "forty" or "twenty-one" stands for.
+fn number(word: &str) -> Option<usize> {
+    if let Some((tens, unit)) = word.split_once('-') {
+        let tens = TENS.iter().position(|t| *t == tens)?;
+
+        return Some(20 + tens * 10 + number_below_ten(unit)?);
+    }
+
+    NUMBER_WORDS.iter().position(|n| *n == word).or_else(|| {
+        TENS.iter()
+            .position(|t| *t == word)
+            .map(|tens| 20 + tens * 10)
+    })
+}
+
+fn number_below_ten(word: &str) -> Option<usize> {
+    NUMBER_WORDS[1..10]
+        .iter()
+        .position(|n| *n == word)
+        .map(|unit| unit + 1)
+}
+
+/// The edit distance between two strings, counted in characters, where
+/// swapping two neighbouring letters counts as a single edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalise_drops_case_articles_and_punctuation() {
+        let matching = AnswerMatching::default();
+
+        assert_eq!(matching.normalise("A Piano!"), "piano");
+        assert_eq!(matching.normalise("  The   car-door "), "car door");
+        assert_eq!(matching.normalise("it's"), "its");
+        assert_eq!(matching.normalise("Three ducks"), "3 ducks");
+        assert_eq!(matching.normalise("twenty-one"), "21");
+        assert_eq!(matching.normalise("Twenty one ducks"), "21 ducks");
+        assert_eq!(matching.normalise("ninety-nine"), "99");
+        assert_eq!(matching.normalise("forty"), "40");
+        assert_eq!(matching.normalise("twenty twenty"), "20 20");
+        assert_eq!(matching.normalise("one two"), "1 2");
+        assert_eq!(matching.normalise("the"), "");
+    }
+
+    #[test]
+    fn compound_numbers_keep_their_hyphen_without_ignoring_punctuation() {
+        let matching = AnswerMatching {
+            ignore_punctuation: false,
+            ..AnswerMatching::default()
+        };
+
+        assert_eq!(matching.normalise("twenty-one"), "21");
+        assert_eq!(matching.normalise("twenty-twenty"), "twenty-twenty");
+        assert_eq!(matching.normalise("car-door"), "car-door");
+    }
+
+    #[test]
+    fn exact_normalise_only_splits_words() {
+        let matching = AnswerMatching::exact();
+
+        assert_eq!(matching.normalise("A Piano!"), "A Piano!");
+        assert_eq!(matching.normalise("  three   ducks "), "three ducks");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("clock", "clock"), 0);
+        assert_eq!(edit_distance("clock", "clok"), 1);
+        assert_eq!(edit_distance("clock", "clocks"), 1);
+        assert_eq!(edit_distance("coin", "corn"), 1);
+        assert_eq!(edit_distance("clock", "clcok"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("ab", ""), 2);
+    }
+
+    #[test]
+    fn edit_distance_counts_characters_not_bytes() {
+        assert_eq!(edit_distance("café", "cafe"), 1);
+        assert_eq!(edit_distance("naïve", "naïve"), 0);
+    }
+
+    #[test]
+    fn typos_are_off_by_default() {
+        let answers = ["coin".to_string()];
+
+        assert!(!AnswerMatching::default().accepts("", &answers, "corn"));
+
+        let forgiving = AnswerMatching {
+            max_typos: 1,
+            ..AnswerMatching::default()
+        };
+
+        assert!(forgiving.accepts("", &answers, "coni"));
+        assert!(!forgiving.accepts("", &["it".to_string()], "at"));
+    }
+}
@@ -0,0 +1,131 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Stats;
+use crate::structs::Item;
+
+/// How many times a healer can heal itself in one battle, once it's out of healing items.
+pub const MAX_HEALS: u32 = 3;
+
+/// How an enemy fights, set by `behaviour` in the map file.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Behaviour {
+    /// Attacks every round, and uses a random item half the time.
+    #[default]
+    Balanced,
+    /// Hits harder for the first few rounds, and only uses items that help it attack.
+    Aggressive,
+    /// Guards every other round, instead of attacking, when the player hits harder than it does.
+    Defensive,
+    /// Below half health, uses its best healing item, and heals itself instead of attacking.
+    Healer,
+    /// Below a third of its health, hits twice as hard but lowers its guard.
+    Berserker,
+    /// Runs away below a quarter of its health, leaving the door open.
+    Fleeing,
+}
+
+/// How a battle stands at the start of an enemy's turn.
+#[derive(Debug, Clone, Copy)]
+pub struct Situation {
+    /// Starting at 1.
+    pub round: u32,
+    pub health: i32,
+    /// The enemy's health when the battle started.
+    pub max_health: i32,
+    /// How many times it has healed itself so far.
+    pub heals: u32,
+    pub enemy: Stats,
+    pub player: Stats,
+}
+
+/// What an enemy does with its turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Attack,
+    /// Doubles its defense for the round, and doesn't attack.
+    Defend,
+    /// Heals by this much, and doesn't attack.
+    Heal(i32),
+    Flee,
+}
+
+impl Situation {
+    fn below(&self, fraction: i32) -> bool {
+        (self.health as i64) * (fraction as i64) < self.max_health as i64
+    }
+}
+
+impl Behaviour {
+    /// What the enemy does this round, on top of any item it uses.
+    pub fn action(&self, situation: &Situation) -> Action {
+        match self {
+            Behaviour::Defensive if situation.round.is_multiple_of(2) && outmatched(situation) => {
+                Action::Defend
+            }
+            Behaviour::Healer if situation.below(2) && situation.heals < MAX_HEALS => {
+                Action::Heal((situation.max_health / 5).max(1))
+            }
+            Behaviour::Fleeing if situation.below(4) => Action::Flee,
+            _ => Action::Attack,
+        }
+    }
+
+    /// The enemy's attack this round, as a percentage of its usual attack.
+    pub fn attack_percent(&self, situation: &Situation) -> i32 {
+        match self {
+            Behaviour::Aggressive if situation.round <= 3 => 125,
+            Behaviour::Berserker if self.is_enraged(situation) => 200,
+            _ => 100,
+        }
+    }
+
+    /// The enemy's defense this round, as a percentage of its usual defense.
+    pub fn defense_percent(&self, situation: &Situation, action: Action) -> i32 {
+        match self {
+            _ if action == Action::Defend => 200,
+            Behaviour::Berserker if self.is_enraged(situation) => 50,
+            _ => 100,
+        }
+    }
+
+    pub fn is_enraged(&self, situation: &Situation) -> bool {
+        *self == Behaviour::Berserker && situation.below(3)
+    }
+
+    /// Which of `items` the enemy uses before it acts, if any.
+    pub fn pick_item(
+        &self,
+        situation: &Situation,
+        items: &[Item],
+        rng: &mut impl Rng,
+    ) -> Option<usize> {
+        let best = |score: fn(&Item) -> i32| {
+            (0..items.len())
+                .filter(|&i| score(&items[i]) > 0)
+                .max_by_key(|&i| score(&items[i]))
+        };
+
+        match self {
+            Behaviour::Aggressive => best(|item| item.attack),
+            Behaviour::Healer if situation.below(2) => best(|item| item.health),
+            Behaviour::Healer => None,
+            Behaviour::Berserker if self.is_enraged(situation) => None,
+            _ if !items.is_empty() && rng.gen_bool(0.5) => Some(rng.gen_range(0..items.len())),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the player hits the enemy harder than it hits back.
+fn outmatched(situation: &Situation) -> bool {
+    situation
+        .player
+        .attack
+        .saturating_sub(situation.enemy.defense)
+        > situation
+            .enemy
+            .attack
+            .saturating_sub(situation.player.defense)
+}
@@ -0,0 +1,847 @@
+use std::path::PathBuf;
+
+use console::style;
+
+use crate::content::Content;
+use crate::engine::{BattleAction, Command, Engine, Event, Prompt};
+use crate::io::Io;
+use crate::rng::GameRng;
+use crate::save::{self, SaveManager};
+use crate::structs::{Class, Ending, Item, Key, Pickup, Player, Summary};
+
+/// Flags the front-end was started with.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub debug: bool,
+    pub no_save: bool,
+    /// Seeds a new game's random rolls. Loaded games keep their saved state.
+    pub seed: Option<u64>,
+    /// Whether a new game is marked as over in its save when it's lost.
+    pub permadeath: bool,
+    /// Where saves are kept, if not the [default](crate::save::default_dir).
+    pub save_dir: Option<PathBuf>,
+    /// How many autosaves to keep for each game. 0 turns autosaving off.
+    pub backups: usize,
+}
+
+impl Options {
+    pub fn saves(&self) -> SaveManager {
+        match &self.save_dir {
+            Some(dir) => SaveManager::new(dir),
+            None => SaveManager::default(),
+        }
+    }
+}
+
+fn truncate_string(s: &str, n: usize) -> String {
+    if s.len() <= n {
+        return s.to_string();
+    }
+
+    let mut new_s = s.to_string();
+    new_s.truncate(n);
+    new_s.push_str("...");
+
+    new_s
+}
+
+/// Runs a whole game over `io`: loading or creating a player, then the command loop.
+pub fn play(io: &mut dyn Io, content: Content, options: &Options) {
+    if let Some(player) = start(io, &content, options) {
+        let map = content.map;
+
+        let mut riddles = content.riddles;
+        riddles.on_wrong_answer = map.wrong_answer_policy(&riddles).clone();
+
+        run(
+            io,
+            &mut Engine::new(player)
+                .with_riddles(riddles)
+                .with_saves(options.saves())
+                .with_autosave(if options.no_save { 0 } else { options.backups })
+                .with_rules(map.rules)
+                .with_map(map.rooms)
+                .with_conditions(map.win_condition, map.lose_condition),
+            options,
+        );
+    }
+}
+
+/// Asks whether to load a savegame, and otherwise sets up a new player.
+pub fn start(io: &mut dyn Io, content: &Content, options: &Options) -> Option<Player> {
+    let load = io.prompt("Would you like to load a savegame? (y/n)", "yellow")?;
+
+    if load.trim() == "y" {
+        let saves = options.saves();
+
+        list_saves(io, &saves);
+
+        let savegame_name = io.prompt("What is the name of the savegame?", "yellow")?;
+
+        let savegame_name = savegame_name.trim();
+
+        let (player, changed) = match saves.load(savegame_name).and_then(|save| {
+            let changed = (!save.same_map(&content.map.rooms)).then(|| save.game_version.clone());
+
+            save.restore(&content.map.rooms)
+                .map(|player| (player, changed))
+        }) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                io.write(format!("Could not load savegame: {}", e).as_str(), "red");
+                return None;
+            }
+        };
+
+        if player.ending.is_some() {
+            io.write("That adventure is already over.", "red");
+            render(io, &Event::GameOver(player.summary()));
+            return None;
+        }
+
+        if let Some(game_version) = changed {
+            render(io, &Event::MapChanged(game_version));
+        }
+
+        io.write(
+            format!("You are in the {}", player.current_room).as_str(),
+            "blue",
+        );
+
+        Some(player)
+    } else {
+        let class = choose_class(io, &content.classes);
+
+        let mut player = Player::init(
+            "Player".to_string(),
+            content.map.rooms.clone(),
+            "".to_string(),
+            content.map.start_room.clone(),
+            class,
+        );
+
+        player.rng = Some(
+            options
+                .seed
+                .map_or_else(GameRng::from_entropy, GameRng::new),
+        );
+        player.permadeath = options.permadeath;
+
+        player.name = io
+            .prompt("What is your name?", "yellow")?
+            .trim()
+            .to_string();
+
+        player.game_name = io
+            .prompt("What is the name of this game?", "yellow")?
+            .trim()
+            .to_string();
+
+        if !content.map.title.is_empty() {
+            io.write(content.map.title.as_str(), "magenta");
+        }
+
+        if let Some(intro) = &content.map.intro {
+            io.write(intro.as_str(), "white");
+        }
+
+        io.write(
+            format!(
+                "Welcome to the game, {}! You are in the {}",
+                player.name, player.current_room
+            )
+            .as_str(),
+            "blue",
+        );
+
+        if let Some(win) = &content.map.win_condition {
+            io.write(format!("Your goal: {}", win).as_str(), "yellow");
+        }
+
+        if let Some(lose) = &content.map.lose_condition {
+            io.write(
+                format!("The game is over if you {}.", lose).as_str(),
+                "yellow",
+            );
+        }
+
+        Some(player)
+    }
+}
+
+/// Prints the saves there are to choose from.
+pub fn list_saves(io: &mut dyn Io, saves: &SaveManager) {
+    let list = match saves.list() {
+        Ok(list) => list,
+        Err(e) => {
+            io.write(format!("Could not list savegames: {}", e).as_str(), "red");
+            return;
+        }
+    };
+
+    if list.is_empty() {
+        io.write(
+            format!("There are no savegames in {}", saves.dir().display()).as_str(),
+            "yellow",
+        );
+        return;
+    }
+
+    io.write(
+        format!("Savegames in {}:", saves.dir().display()).as_str(),
+        "yellow",
+    );
+
+    for save in list {
+        io.write(
+            format!(
+                "- {}: {} the {}, in the {} ({})",
+                save.name,
+                save.player,
+                save.class,
+                save.room,
+                save::format_time(save.saved_at)
+            )
+            .as_str(),
+            "yellow",
+        );
+    }
+}
+
+/// The command loop. Returns once the player quits, the game ends or the input runs out.
+pub fn run(io: &mut dyn Io, engine: &mut Engine, options: &Options) {
+    while !engine.is_over() {
+        let Some(input) = io.read_line() else {
+            break;
+        };
+        let input = input.trim();
+
+        if engine.is_waiting() {
+            render_all(io, engine.handle(Command::Answer(input.to_string())));
+            continue;
+        }
+
+        let commands: Vec<&str> = input.splitn(2, ' ').collect();
+
+        let argument = commands.get(1).map(|arg| arg.to_string());
+
+        if options.debug {
+            if commands[0] == "give" {
+                let item = Item::new(
+                    argument.unwrap_or_default(),
+                    "a debug item".to_string(),
+                    100,
+                    100,
+                    100,
+                );
+
+                engine.player_mut().items_held.push(item);
+
+                continue;
+            } else if commands[0] == "givekey" {
+                let key = Key::new(argument.unwrap_or_default());
+
+                engine.player_mut().keys_held.push(key);
+
+                continue;
+            }
+        }
+
+        let command = match (commands[0], argument) {
+            ("quit", _) => {
+                io.write("Goodbye!", "green");
+                save(io, engine, options.no_save);
+                break;
+            }
+
+            ("help", _) => {
+                io.write(
+                    format!(
+                        "
+{}
+- look: print the description of the room
+- go [room]: move to another room (checks if you have the key)
+- take [item]: take an item
+- takekey [key]: take a key
+- unlock [door]: unlock a door for good, with a key you have
+- lock [door]: lock a door again, with a key you have
+- search: search the room for items, keys, and doors
+- save: save the game
+- load [savegame]: load a savegame in place of this one, or list them
+- battles: print the battles you've fought
+- inventory: print the items and keys you have
+- use: use an item now, but for only half the effect",
+                        style("Commands:").bold()
+                    )
+                    .as_str(),
+                    "green",
+                );
+
+                if options.debug {
+                    io.write(
+                        format!(
+                            "{}
+- debug: print the current room's data",
+                            style("Debug Commands:").bold()
+                        )
+                        .as_str(),
+                        "green",
+                    );
+                }
+
+                io.write(
+                    "If you can't figure out what to do, try using `search`!\nStuck for a key? Make sure to look around!",
+                    "cyan",
+                );
+                continue;
+            }
+
+            ("debug", _) => {
+                io.write(format!("{:?}", engine.player().room()).as_str(), "white");
+                io.write(format!("seed: {}", engine.seed()).as_str(), "white");
+                continue;
+            }
+
+            ("save", _) => {
+                save(io, engine, options.no_save);
+                continue;
+            }
+
+            ("load", None) => {
+                list_saves(io, &options.saves());
+                io.write(
+                    "Type `load` and the name of the savegame to load it.",
+                    "yellow",
+                );
+                continue;
+            }
+
+            ("go", None) => {
+                io.write("Go where?", "red");
+                continue;
+            }
+            ("unlock", None) => {
+                io.write("Unlock what?", "red");
+                continue;
+            }
+            ("lock", None) => {
+                io.write("Lock what?", "red");
+                continue;
+            }
+            ("take" | "takekey", None) => {
+                io.write("Take what?", "red");
+                continue;
+            }
+            ("use", None) => {
+                io.write("Use what?", "red");
+                continue;
+            }
+
+            ("look", _) => Command::Look,
+            ("go", Some(door)) => Command::Go(door),
+            ("take", Some(item)) => Command::Take(item),
+            ("takekey", Some(key)) => Command::TakeKey(key),
+            ("use", Some(item)) => Command::Use(item),
+            ("unlock", Some(door)) => Command::Unlock(door),
+            ("lock", Some(door)) => Command::Lock(door),
+            ("search", _) => Command::Search,
+            ("battles", _) => Command::Battles,
+            ("inventory", _) => Command::Inventory,
+            ("stats", _) => Command::Stats,
+            ("load", Some(name)) => Command::Load(name),
+
+            _ => {
+                io.write(
+                    "I don't understand that command. Try using `help` if you need it!",
+                    "red",
+                );
+                continue;
+            }
+        };
+
+        render_all(io, engine.handle(command));
+    }
+}
+
+pub fn choose_class(io: &mut dyn Io, classes: &[Class]) -> Class {
+    io.write("Choose a class:", "yellow");
+
+    for class in classes {
+        let mut line = format!(
+            "{}: {} -  {}❤️, {}🪓, {}🛡️",
+            class.name, class.description, class.health, class.attack, class.defense
+        );
+
+        if !class.abilities.is_empty() {
+            let abilities: Vec<String> = class.abilities.iter().map(|a| a.to_string()).collect();
+            line.push_str(format!(" ({})", abilities.join(", ")).as_str());
+        }
+
+        if !class.skills.is_empty() {
+            let skills: Vec<&str> = class.skills.iter().map(|s| s.name.as_str()).collect();
+            line.push_str(format!(" [skills: {}]", skills.join(", ")).as_str());
+        }
+
+        io.write(line.as_str(), "yellow");
+    }
+
+    let input = io.read_line().unwrap_or_default();
+
+    let input = input.trim().to_lowercase();
+
+    let class = classes
+        .iter()
+        .find(|c| c.name.to_lowercase() == input)
+        .unwrap_or(&classes[0]);
+
+    io.write(
+        format!(
+            "You chose the {} class, which has {}❤️, {}🪓 and {}🛡️.",
+            class.name, class.health, class.attack, class.defense
+        )
+        .as_str(),
+        "green",
+    );
+
+    class.clone()
+}
+
+pub fn save(io: &mut dyn Io, engine: &mut Engine, no_save: bool) {
+    if no_save {
+        io.write(
+            "Not saving the game, this was ran on development mode.",
+            "red",
+        );
+        io.write("Manual override (y/n)?", "yellow");
+
+        match io.read_line().unwrap_or_default().as_str() {
+            "y" => {
+                io.write("Saving the game.", "green");
+            }
+            _ => {
+                io.write("Not saving the game.", "red");
+                return;
+            }
+        }
+    }
+
+    render_all(io, engine.handle(Command::Save));
+}
+
+pub fn render_all(io: &mut dyn Io, events: Vec<Event>) {
+    for event in &events {
+        render(io, event);
+    }
+}
+
+pub fn render(io: &mut dyn Io, event: &Event) {
+    match event {
+        Event::RoomEntered(room) => {
+            io.write(format!("You are in the {}", room).as_str(), "blue");
+        }
+        Event::RoomDescription(description) => io.write(description.as_str(), "blue"),
+        Event::ItemSeen(item) => io.write(
+            format!(
+                "You see \"{}\" (item) that buffs {}❤️, {}🪓 and {}🛡️",
+                item.name, item.health, item.attack, item.defense
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::KeySeen(key) => {
+            io.write(format!("You see \"{}\" (key)", key.name).as_str(), "green");
+        }
+        Event::DoorSeen {
+            name,
+            locked,
+            guarded,
+        } => io.write(
+            format!(
+                "You see \"{}\" ({}, {} door)",
+                name,
+                if *locked { "locked" } else { "unlocked" },
+                if *guarded { "guarded" } else { "unguarded" }
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::BattleStarted {
+            player,
+            enemy,
+            class,
+            items,
+        } => {
+            io.write(
+                format!(
+                "You are in a fight! You have to fight the enemy! You have {}❤️, {}🪓 and {}🛡️, the enemy {}❤️, {}🪓 and {}🛡️. Your class is {}.",
+                player.health, player.attack, player.defense, enemy.health, enemy.attack, enemy.defense, class
+                ).as_str(),
+                "magenta"
+            );
+
+            for item in items {
+                io.write(
+                    format!(
+                        "You have the {}, which buffs you {} health and {} attack",
+                        item.name, item.health, item.attack
+                    )
+                    .as_str(),
+                    "magenta",
+                );
+            }
+
+            io.write("Fight!", "magenta");
+        }
+        Event::BattleRound { player, enemy } => io.write(
+            format!(
+                "You have {}❤️, {}🪓 and {}🛡️, the enemy has {}❤️, {}🪓 and {}🛡️.",
+                player.health,
+                player.attack,
+                player.defense,
+                enemy.health,
+                enemy.attack,
+                enemy.defense
+            )
+            .as_str(),
+            "magenta",
+        ),
+        Event::EnemySays { enemy, line } => {
+            io.write(format!("{}: \"{}\"", enemy, line).as_str(), "magenta");
+        }
+        Event::ItemUsed(item) => io.write(
+            format!(
+                "You use the {}, which buffs you {}❤️, {}🪓 and {}🛡️",
+                item.name, item.health, item.attack, item.defense
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::Defending(rounds) => io.write(
+            format!(
+                "You raise your guard, for {} rounds including this one.",
+                rounds
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::SkillUsed(skill) => io.write(format!("You use {}!", skill.name).as_str(), "green"),
+        Event::Healed(health) => io.write(
+            format!("You heal yourself for {}❤️.", health).as_str(),
+            "green",
+        ),
+        Event::EnemyStunned => io.write("The enemy is stunned, and loses its turn!", "green"),
+        Event::FleeFailed => io.write("You try to flee, but the enemy blocks your way!", "red"),
+        Event::Escaped(room) => io.write(
+            format!(
+                "You get away, back into the {}. The enemy still guards the door.",
+                room
+            )
+            .as_str(),
+            "yellow",
+        ),
+        Event::EnemyUsedItem(item) => io.write(
+            format!(
+                "The enemy used the {}, which buffs them {}❤️, {}🪓 and {}🛡️.",
+                item.name, item.health, item.attack, item.defense
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::EnemyHealed(health) => io.write(
+            format!("The enemy heals itself for {}❤️.", health).as_str(),
+            "red",
+        ),
+        Event::EnemyDefended => io.write("The enemy raises its guard.", "yellow"),
+        Event::EnemyEnraged => io.write("The enemy flies into a rage!", "red"),
+        Event::EnemyFled(result) => io.write(
+            format!(
+                "The enemy ran away with {}❤️ left! The way is clear, but you gain nothing from it.",
+                result.enemy_health
+            )
+            .as_str(),
+            "yellow",
+        ),
+        Event::Revived => io.write(
+            "You lost the fight, but you have a second chance! You get back up with your health restored.",
+            "red",
+        ),
+        Event::Dodged => io.write("You dodge the enemy's attack!", "green"),
+        Event::CriticalHit(damage) => io.write(
+            format!("Critical hit! You deal {} damage.", damage).as_str(),
+            "green",
+        ),
+        Event::EnemyCriticalHit(damage) => io.write(
+            format!("A critical hit from the enemy! It deals {} damage.", damage).as_str(),
+            "red",
+        ),
+        Event::Lifesteal(health) => io.write(
+            format!("You steal {}❤️ from the enemy.", health).as_str(),
+            "green",
+        ),
+        Event::BattleWon(_) => io.write(
+            "You won the fight! You gain the enemy's stats they had at the start of the fight.",
+            "green",
+        ),
+        Event::LootLeft(loot) => {
+            let names: Vec<&str> = loot.iter().map(Pickup::name).collect();
+
+            io.write(
+                format!("You leave the {} behind.", names.join(", ")).as_str(),
+                "yellow",
+            );
+        }
+        Event::BattleLost(result) => {
+            io.write("You lost the fight. :-(", "red");
+            io.write(
+                format!(
+                    "The enemy had {} health left (you died with {}).",
+                    result.enemy_health, result.player_health
+                )
+                .as_str(),
+                "red",
+            );
+        }
+        Event::GameOver(summary) => render_summary(io, summary),
+        Event::RiddleAnswered(true) => io.write("Correct!", "green"),
+        Event::RiddleAnswered(false) => io.write("Incorrect!", "red"),
+        Event::ItemTaken(item) => io.write(
+            format!("You took the {} (item)", item.name).as_str(),
+            "green",
+        ),
+        Event::KeyTaken(key) => {
+            io.write(format!("You took the {} (key)", key.name).as_str(), "green");
+        }
+        Event::Unlocked { door, keys } => io.write(
+            format!(
+                "You unlocked the {} with the {} key{}",
+                door,
+                keys.iter()
+                    .map(|k| k.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" and "),
+                if keys.len() == 1 { "" } else { "s" }
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::KeyUsedUp(key) => io.write(
+            format!("The {} key can't be used again", key.name).as_str(),
+            "yellow",
+        ),
+        Event::Locked(door) => io.write(format!("You locked the {}", door).as_str(), "green"),
+        Event::RiddlePenalty(health) => io.write(
+            format!("Wrong answers hurt: you lose {}❤️.", health).as_str(),
+            "red",
+        ),
+        Event::TryAgain(attempts) => io.write(
+            format!(
+                "You got it wrong, but you can try again ({} more {}).",
+                attempts,
+                if *attempts == 1 { "try" } else { "tries" }
+            )
+            .as_str(),
+            "red",
+        ),
+        Event::KeyKept(key) => io.write(
+            format!(
+                "You got it wrong, but the {} (key) stays where it is.",
+                key.name
+            )
+            .as_str(),
+            "red",
+        ),
+        Event::WillRespawn(turns) => io.write(
+            format!("It will be back in {} turns.", turns).as_str(),
+            "yellow",
+        ),
+        Event::Respawned { pickup, room } => io.write(
+            format!("The {} has reappeared in the {}.", pickup.name(), room).as_str(),
+            "yellow",
+        ),
+        Event::ItemDespawned(_) => io.write("You got it wrong, the item despawns.", "red"),
+        Event::KeyDespawned(_) => io.write("You got it wrong, the key despawns.", "red"),
+        Event::Inventory { items, keys } => {
+            for item in items {
+                io.write(
+                    format!(
+                        "You have the \"{}\" (item, \"{}\") that buffs {}❤️, {}🪓 and {}🛡️",
+                        item.name,
+                        truncate_string(item.description.as_str(), 50),
+                        item.health,
+                        item.attack,
+                        item.defense
+                    )
+                    .as_str(),
+                    "green",
+                );
+            }
+
+            for key in keys {
+                io.write(format!("You have the {} (key)", key.name).as_str(), "green");
+            }
+
+            if keys.is_empty() && items.is_empty() {
+                io.write("You have nothing in your inventory.", "red");
+            }
+        }
+        Event::Battles(battles) => {
+            for battle in battles {
+                io.write(
+                    format!(
+                        "You fought \"{}\" and {}. You had {}❤️ and they had {}❤️.",
+                        battle.enemy_name,
+                        if battle.fled {
+                            "they ran away"
+                        } else if battle.winner {
+                            "won"
+                        } else {
+                            "lost"
+                        },
+                        battle.player_health,
+                        battle.enemy_health
+                    )
+                    .as_str(),
+                    "green",
+                );
+            }
+        }
+        Event::Stats { stats, class } => io.write(
+            format!(
+                "You have {}❤️, {}🪓 and {}🛡️. Your class is {}.",
+                stats.health, stats.attack, stats.defense, class
+            )
+            .as_str(),
+            "green",
+        ),
+        Event::Saved(path) => {
+            io.write(format!("Saved the game to {}", path).as_str(), "green");
+        }
+        Event::Autosaved(_) => io.write("Autosaved.", "white"),
+        Event::SaveEnded(_) => io.write(
+            "Permadeath is on, so this save is marked as over and can't be loaded again.",
+            "red",
+        ),
+        Event::Loaded(name) => io.write(format!("Loaded {}.", name).as_str(), "green"),
+        Event::MapChanged(game_version) => io.write(
+            format!(
+                "Warning: this savegame was made on a different map, or another version of this one (saved by gext {}), so things may not be where you left them.",
+                game_version
+            )
+            .as_str(),
+            "yellow",
+        ),
+        Event::Prompt(Prompt::ChooseAction(actions)) => {
+            let actions: Vec<String> = actions
+                .iter()
+                .map(|action| match action {
+                    BattleAction::Skill(skill) => format!("{} ({} left)", skill.name, skill.uses),
+                    _ => action.to_string(),
+                })
+                .collect();
+
+            io.write(
+                format!("What do you do? {}", actions.join(", ")).as_str(),
+                "magenta",
+            );
+        }
+        Event::Prompt(Prompt::ChooseItem(items)) => {
+            io.write("Which item do you want to use?", "magenta");
+
+            for item in items {
+                io.write(
+                    format!(
+                        "You have the {}, which buffs you {}❤️, {}🪓 and {}🛡️",
+                        item.name, item.health, item.attack, item.defense
+                    )
+                    .as_str(),
+                    "magenta",
+                );
+            }
+        }
+        Event::Prompt(Prompt::Riddle(riddle)) => {
+            io.write(riddle.question.as_str(), "yellow");
+
+            if riddle.hint.is_some() {
+                io.write("(stuck? answer `hint` for a hint)", "cyan");
+            }
+        }
+        Event::Prompt(Prompt::Loot(loot)) => {
+            io.write("The enemy dropped:", "yellow");
+
+            for pickup in loot {
+                let line = match pickup {
+                    Pickup::Item(item) => format!(
+                        "- the {} (item), which buffs you {}❤️, {}🪓 and {}🛡️",
+                        item.name, item.health, item.attack, item.defense
+                    ),
+                    Pickup::Key(key) => format!("- the {} (key)", key.name),
+                };
+
+                io.write(line.as_str(), "yellow");
+            }
+
+            io.write(
+                "Type a name to take it, `all` to take everything, or `leave` to leave the rest here.",
+                "magenta",
+            );
+        }
+        Event::Hint(hint) => io.write(format!("Hint: {}", hint).as_str(), "cyan"),
+        Event::Error(err) => io.write(err.to_string().as_str(), "red"),
+    }
+}
+
+/// The final score screen.
+pub fn render_summary(io: &mut dyn Io, summary: &Summary) {
+    match summary.ending {
+        Some(Ending::Won) => io.write(
+            format!("{}", style("You won! Congratulations!").bold()).as_str(),
+            "green",
+        ),
+        _ => io.write(
+            format!("{}", style("Your adventure ends here.").bold()).as_str(),
+            "red",
+        ),
+    }
+
+    io.write(
+        format!(
+            "{} the {}, after {} turns:",
+            summary.name, summary.class, summary.turns
+        )
+        .as_str(),
+        "white",
+    );
+    io.write(
+        format!(
+            "- battles: {} won, {} lost",
+            summary.battles_won, summary.battles_lost
+        )
+        .as_str(),
+        "white",
+    );
+
+    if !summary.defeated.is_empty() {
+        io.write(
+            format!("- defeated: {}", summary.defeated.join(", ")).as_str(),
+            "white",
+        );
+    }
+
+    io.write(
+        format!(
+            "- finished with {}❤️, {}🪓 and {}🛡️",
+            summary.stats.health, summary.stats.attack, summary.stats.defense
+        )
+        .as_str(),
+        "white",
+    );
+    io.write(
+        format!(
+            "- holding {} items and {} keys",
+            summary.items, summary.keys
+        )
+        .as_str(),
+        "white",
+    );
+    io.write(format!("Score: {}", summary.score).as_str(), "magenta");
+}
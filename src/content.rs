@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+
+use crate::map::{link_doors, Map};
+use crate::riddles::{RiddleChoice, RiddlePack};
+use crate::structs::{Ability, Class, Room};
+
+/// Everything a game is built from: the map, the classes on offer and the riddles.
+#[derive(Debug, Clone)]
+pub struct Content {
+    pub map: Map,
+    pub classes: Vec<Class>,
+    pub riddles: RiddlePack,
+}
+
+impl Default for Content {
+    fn default() -> Content {
+        Content {
+            map: Map::builtin(),
+            classes: Class::builtins(),
+            riddles: RiddlePack::builtin(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ContentError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContentError::Io(err) => write!(f, "could not open file: {}", err),
+            ContentError::Parse(err) => write!(f, "could not parse file: {}", err),
+            ContentError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+impl From<std::io::Error> for ContentError {
+    fn from(err: std::io::Error) -> ContentError {
+        ContentError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ContentError {
+    fn from(err: serde_json::Error) -> ContentError {
+        ContentError::Parse(err)
+    }
+}
+
+/// Loads a `*.map.json` file, either a [`Map`] or, in the older format, just its rooms.
+///
+/// This only checks that the file parses; see [`validate_map`](crate::validate::validate_map).
+pub fn load_map(path: &str) -> Result<Map, ContentError> {
+    let value: serde_json::Value = serde_json::from_reader(File::open(path)?)?;
+
+    // the old format is just an object of rooms, which can't pass for a map, even with rooms
+    // called "rooms" or "doors"
+    let document = match serde_json::from_value::<Map>(value.clone()) {
+        Ok(mut map) => {
+            link_doors(&mut map.rooms);
+            return Ok(map);
+        }
+        Err(e) => e,
+    };
+
+    match serde_json::from_value::<HashMap<String, Room>>(value.clone()) {
+        Ok(rooms) => Ok(Map::from_rooms(rooms)),
+        // a broken map is more use reported as a map than as rooms
+        Err(_) if value.get("rooms").is_some() => Err(document.into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Loads a `*.classes.json` file: a JSON array of classes.
+pub fn load_classes(path: &str) -> Result<Vec<Class>, ContentError> {
+    let classes: Vec<Class> = serde_json::from_reader(File::open(path)?)?;
+
+    validate_classes(&classes)?;
+
+    Ok(classes)
+}
+
+pub fn validate_classes(classes: &[Class]) -> Result<(), ContentError> {
+    if classes.is_empty() {
+        return Err(ContentError::Invalid(
+            "there must be at least one class".to_string(),
+        ));
+    }
+
+    let mut names = HashSet::new();
+
+    for class in classes {
+        if class.name.trim().is_empty() {
+            return Err(ContentError::Invalid(
+                "every class needs a name".to_string(),
+            ));
+        }
+
+        // classes are picked by typing their name, case-insensitively
+        if !names.insert(class.name.to_lowercase()) {
+            return Err(ContentError::Invalid(format!(
+                "there is more than one class called \"{}\"",
+                class.name
+            )));
+        }
+
+        if class.health <= 0 {
+            return Err(ContentError::Invalid(format!(
+                "the \"{}\" class must start with more than 0 health",
+                class.name
+            )));
+        }
+
+        if class.attack < 0 || class.defense < 0 {
+            return Err(ContentError::Invalid(format!(
+                "the \"{}\" class can't start with negative attack or defense",
+                class.name
+            )));
+        }
+
+        for ability in &class.abilities {
+            let chance = match ability {
+                Ability::Dodge { chance } | Ability::Crit { chance, .. } => *chance,
+                _ => 0,
+            };
+
+            if chance > 100 {
+                return Err(ContentError::Invalid(format!(
+                    "the \"{}\" class has an ability with more than a 100% chance",
+                    class.name
+                )));
+            }
+
+            // the same bound as the map's crit_multiplier rule
+            if matches!(ability, Ability::Crit { multiplier, .. } if *multiplier < 100) {
+                return Err(ContentError::Invalid(format!(
+                    "the \"{}\" class has a crit multiplier below 100%, so its critical hits would do less damage",
+                    class.name
+                )));
+            }
+        }
+
+        // skills are chosen in battle by typing their name, alongside the actions every class has
+        let mut skill_names = HashSet::from(["attack", "defend", "item", "flee"].map(String::from));
+
+        for skill in &class.skills {
+            if skill.name.trim().is_empty() {
+                return Err(ContentError::Invalid(format!(
+                    "every skill of the \"{}\" class needs a name",
+                    class.name
+                )));
+            }
+
+            if !skill_names.insert(skill.name.clone()) {
+                return Err(ContentError::Invalid(format!(
+                    "the \"{}\" class has a skill called \"{}\", which is taken",
+                    class.name, skill.name
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads a `*.riddles.json` riddle pack.
+pub fn load_riddles(path: &str) -> Result<RiddlePack, ContentError> {
+    let riddles: RiddlePack = serde_json::from_reader(File::open(path)?)?;
+
+    validate_riddles(&riddles)?;
+
+    Ok(riddles)
+}
+
+/// The riddle pack a map names in its header, found relative to the map file.
+pub fn map_riddles_path(map_path: &str, map: &Map) -> Option<String> {
+    let riddles = map.riddles.as_ref()?;
+    let dir = Path::new(map_path).parent().unwrap_or(Path::new(""));
+
+    Some(dir.join(riddles).to_string_lossy().into_owned())
+}
+
+/// The riddle pack that goes with a map by default: `castle.map.json` pairs with `castle.riddles.json`.
+pub fn sibling_riddles_path(map_path: &str) -> Option<String> {
+    map_path
+        .strip_suffix(".map.json")
+        .map(|stem| format!("{}.riddles.json", stem))
+}
+
+pub fn validate_riddles(pack: &RiddlePack) -> Result<(), ContentError> {
+    if pack.riddles.is_empty() {
+        return Err(ContentError::Invalid(
+            "there must be at least one riddle".to_string(),
+        ));
+    }
+
+    if pack.on_wrong_answer.attempts == 0 {
+        return Err(ContentError::Invalid(
+            "on_wrong_answer.attempts must be at least 1".to_string(),
+        ));
+    }
+
+    if pack.on_wrong_answer.health_cost < 0 {
+        return Err(ContentError::Invalid(
+            "on_wrong_answer.health_cost can't be negative".to_string(),
+        ));
+    }
+
+    let mut ids = HashSet::new();
+
+    for riddle in &pack.riddles {
+        if !ids.insert(riddle.id.as_str()) {
+            return Err(ContentError::Invalid(format!(
+                "there is more than one riddle with the id \"{}\"",
+                riddle.id
+            )));
+        }
+
+        if riddle.question.trim().is_empty() {
+            return Err(ContentError::Invalid(format!(
+                "the \"{}\" riddle has no question",
+                riddle.id
+            )));
+        }
+
+        if riddle.answers.iter().all(|a| a.trim().is_empty()) {
+            return Err(ContentError::Invalid(format!(
+                "the \"{}\" riddle has no answers",
+                riddle.id
+            )));
+        }
+    }
+
+    for (kind, choices) in [("item", &pack.items), ("key", &pack.keys)] {
+        for (name, choice) in choices {
+            if !pack.riddles.iter().any(|r| choice.matches(r)) {
+                return Err(ContentError::Invalid(match choice {
+                    RiddleChoice::Id(id) => format!(
+                        "the \"{}\" {} is guarded by the \"{}\" riddle, which doesn't exist",
+                        name, kind, id
+                    ),
+                    RiddleChoice::Filter { .. } => format!(
+                        "no riddle matches the one asked for the \"{}\" {}",
+                        name, kind
+                    ),
+                }));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How damage is worked out, set by the `rules` section of a map.
+///
+/// Each round both sides roll their attack, which is their attack stat give or take `variance`
+/// percent of it. A hit does the attack roll minus the other side's defense, scaled by any skill
+/// and then by a critical hit if one lands, but never less than `min_damage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// How far attack rolls stray from the attack stat, as a percentage of it.
+    pub variance: u32,
+    /// The least damage a hit does, however high the defense it hits.
+    pub min_damage: u32,
+    /// The chance of any hit being critical, for enemies and for classes without a crit ability.
+    pub crit_chance: u32,
+    /// How hard those critical hits land, as a percentage of the usual damage.
+    pub crit_multiplier: u32,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            variance: 25,
+            min_damage: 0,
+            crit_chance: 0,
+            crit_multiplier: 150,
+        }
+    }
+}
+
+/// `percent` percent of `value`, stopping at the ends of `i32` rather than overflowing.
+pub fn percent_of(value: i32, percent: i64) -> i32 {
+    ((value as i64).saturating_mul(percent) / 100).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// The damage one hit did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub damage: i32,
+    pub critical: bool,
+}
+
+impl Rules {
+    pub fn is_default(&self) -> bool {
+        *self == Rules::default()
+    }
+
+    /// How far either way an attack roll can stray from `attack`.
+    pub fn spread(&self, attack: i32) -> i32 {
+        (attack.max(0) as i64 * self.variance.min(100) as i64 / 100) as i32
+    }
+
+    /// An attack roll: `attack`, give or take [`spread`](Rules::spread) of it.
+    pub fn roll(&self, rng: &mut impl Rng, attack: i32) -> i32 {
+        let spread = self.spread(attack);
+
+        if spread == 0 {
+            return attack;
+        }
+
+        attack.saturating_add(rng.gen_range(-spread..=spread))
+    }
+
+    /// The crit chance and multiplier a side strikes with: its own if it has one, or else the
+    /// map's, if the map gives crits a chance.
+    pub fn crit(&self, own: Option<(u32, u32)>) -> Option<(u32, u32)> {
+        own.or(Some((self.crit_chance, self.crit_multiplier)))
+            .filter(|(chance, _)| *chance > 0)
+    }
+
+    /// The damage of a hit with an attack roll of `attack` against `defense`, for `percent` of
+    /// the usual damage, and critical if `multiplier` is given.
+    pub fn damage(&self, attack: i32, defense: i32, percent: u32, multiplier: Option<u32>) -> i32 {
+        let mut damage = (attack as i64 - defense as i64)
+            .max(0)
+            .saturating_mul(percent as i64)
+            / 100;
+
+        if let Some(multiplier) = multiplier {
+            damage = damage.saturating_mul(multiplier as i64) / 100;
+        }
+
+        (damage.min(i32::MAX as i64) as i32).max(self.min_damage.min(i32::MAX as u32) as i32)
+    }
+
+    /// Strikes with an attack roll of `attack` against `defense`, for `percent` of the usual
+    /// damage. `crit` is the striker's own crit chance and multiplier, if it has one.
+    pub fn hit(
+        &self,
+        rng: &mut impl Rng,
+        attack: i32,
+        defense: i32,
+        percent: u32,
+        crit: Option<(u32, u32)>,
+    ) -> Hit {
+        let multiplier = match self.crit(crit) {
+            Some((chance, multiplier)) if rng.gen_range(0..100) < chance => Some(multiplier),
+            _ => None,
+        };
+
+        Hit {
+            damage: self.damage(attack, defense, percent, multiplier),
+            critical: multiplier.is_some(),
+        }
+    }
+
+    /// The damage a hit does on average, counting crits.
+    pub fn average_damage(
+        &self,
+        attack: i32,
+        defense: i32,
+        percent: u32,
+        crit: Option<(u32, u32)>,
+    ) -> f64 {
+        let plain = self.damage(attack, defense, percent, None) as f64;
+
+        match self.crit(crit) {
+            Some((chance, multiplier)) => {
+                let chance = chance.min(100) as f64 / 100.0;
+                let critical = self.damage(attack, defense, percent, Some(multiplier)) as f64;
+
+                plain * (1.0 - chance) + critical * chance
+            }
+            None => plain,
+        }
+    }
+}
@@ -0,0 +1,1457 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rand::Rng;
+
+use crate::answers::AnswerMatching;
+use crate::behaviour::{Action, Situation};
+use crate::damage::{percent_of, Rules};
+use crate::map::{LoseCondition, WinCondition};
+use crate::riddles::{Riddle, RiddlePack};
+use crate::rng::GameRng;
+use crate::save::{SaveFile, SaveManager};
+use crate::structs::{
+    BattleResult, Despawned, Door, Ending, Enemy, Item, Key, Pickup, Player, Room, Skill,
+    SkillEffect, Summary,
+};
+
+/// The chance, as a percentage, that the player gets away when they flee a battle.
+pub const FLEE_CHANCE: u32 = 50;
+/// How many rounds defending lasts, counting the one it's chosen in.
+pub const DEFEND_ROUNDS: u32 = 2;
+/// The player's defense while defending, as a percentage of their usual defense.
+pub const DEFEND_PERCENT: i32 = 150;
+
+/// A single action the player can take, passed to [`Engine::handle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Look,
+    Go(String),
+    Take(String),
+    TakeKey(String),
+    Use(String),
+    Unlock(String),
+    Lock(String),
+    Search,
+    Battles,
+    Inventory,
+    Stats,
+    Save,
+    /// Swaps the game for a saved one, by the name it was saved under.
+    Load(String),
+    /// A reply to the last [`Prompt`] the engine emitted.
+    Answer(String),
+}
+
+/// Something the engine is waiting on before it can carry on.
+#[derive(Debug, Clone)]
+pub enum Prompt {
+    /// What to do this round of a battle. Answered with one of the actions' names.
+    ChooseAction(Vec<BattleAction>),
+    ChooseItem(Vec<Item>),
+    Riddle(Riddle),
+    /// What a beaten enemy dropped. Answered with a name to take it, `all` to take everything,
+    /// or `leave` to leave the rest in the room.
+    Loot(Vec<Pickup>),
+}
+
+/// Something the player can do with their turn in a battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BattleAction {
+    Attack,
+    /// Raises the player's defense for [`DEFEND_ROUNDS`] rounds, instead of attacking.
+    Defend,
+    /// Uses an item instead of attacking, asking which with [`Prompt::ChooseItem`].
+    UseItem,
+    /// Tries to back away into the room the player came from.
+    Flee,
+    /// A class skill, with the number of uses it has left this battle.
+    Skill(Skill),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub health: i32,
+    pub attack: i32,
+    pub defense: i32,
+}
+
+/// What happened as the result of a [`Command`].
+#[derive(Debug, Clone)]
+pub enum Event {
+    RoomEntered(String),
+    RoomDescription(String),
+    ItemSeen(Item),
+    KeySeen(Key),
+    DoorSeen {
+        name: String,
+        locked: bool,
+        guarded: bool,
+    },
+    BattleStarted {
+        player: Stats,
+        enemy: Stats,
+        class: String,
+        items: Vec<Item>,
+    },
+    BattleRound {
+        player: Stats,
+        enemy: Stats,
+    },
+    /// A line of an enemy's [`Dialogue`](crate::structs::Dialogue).
+    EnemySays {
+        enemy: String,
+        line: String,
+    },
+    ItemUsed(Item),
+    /// The player is defending, until this many more rounds have passed.
+    Defending(u32),
+    SkillUsed(Skill),
+    /// Health the player got back from a skill.
+    Healed(i32),
+    /// The enemy loses its turn this round.
+    EnemyStunned,
+    /// The player tried to flee, but the enemy kept them in the fight.
+    FleeFailed,
+    /// The player got away from the battle, back into this room.
+    Escaped(String),
+    EnemyUsedItem(Item),
+    /// The enemy healed itself by this much, instead of attacking.
+    EnemyHealed(i32),
+    /// The enemy guarded this round, instead of attacking.
+    EnemyDefended,
+    /// A berserker dropped low enough on health to fly into a rage.
+    EnemyEnraged,
+    /// The enemy ran away, leaving the way through the door open.
+    EnemyFled(BattleResult),
+    /// The player's class ability brought them back from 0 health.
+    Revived,
+    Dodged,
+    CriticalHit(i32),
+    EnemyCriticalHit(i32),
+    Lifesteal(i32),
+    BattleWon(BattleResult),
+    /// Loot that wasn't taken, left lying in the room.
+    LootLeft(Vec<Pickup>),
+    BattleLost(BattleResult),
+    Hint(String),
+    RiddleAnswered(bool),
+    /// Health lost for a wrong answer.
+    RiddlePenalty(i32),
+    /// The answer was wrong, but the item or key can be tried for again this many more times.
+    TryAgain(u32),
+    KeyKept(Key),
+    /// Something that despawned will come back after this many turns.
+    WillRespawn(u64),
+    Respawned {
+        pickup: Pickup,
+        room: String,
+    },
+    ItemTaken(Item),
+    KeyTaken(Key),
+    ItemDespawned(Item),
+    KeyDespawned(Key),
+    Unlocked {
+        door: String,
+        keys: Vec<Key>,
+    },
+    /// A single-use key was used up.
+    KeyUsedUp(Key),
+    Locked(String),
+    Inventory {
+        items: Vec<Item>,
+        keys: Vec<Key>,
+    },
+    Battles(Vec<BattleResult>),
+    Stats {
+        stats: Stats,
+        class: String,
+    },
+    Saved(String),
+    Autosaved(String),
+    /// With permadeath, the save at this path was marked as over, so it can't be played on.
+    SaveEnded(String),
+    /// A savegame was loaded in place of the game being played.
+    Loaded(String),
+    /// The savegame being loaded was made on a different map, or another version of this one, and
+    /// was saved by this version of gext.
+    MapChanged(String),
+    /// The win or lose condition was met. Only [`Command::Save`] works after this.
+    GameOver(Summary),
+    Prompt(Prompt),
+    Error(EngineError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    NoSuchDoor,
+    DoorLocked,
+    NotLocked,
+    AlreadyLocked,
+    /// The door can't be locked, as it has no key.
+    NoLock,
+    NoFittingKey,
+    RequirementsNotMet {
+        required: Stats,
+        actual: Stats,
+    },
+    NoSuchItem,
+    NoSuchKey,
+    DontHaveItem,
+    /// The answer isn't one of the actions offered this round.
+    UnknownAction,
+    AwaitingAnswer,
+    NothingToAnswer,
+    GameOver,
+    SaveFailed(String),
+    LoadFailed(String),
+    /// The savegame being loaded has already been won or lost.
+    SaveOver,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EngineError::NoSuchDoor => write!(f, "I find no such door"),
+            EngineError::DoorLocked => write!(f, "The door is locked."),
+            EngineError::NotLocked => write!(f, "That door isn't locked."),
+            EngineError::AlreadyLocked => write!(f, "That door is already locked."),
+            EngineError::NoLock => write!(f, "That door has no lock."),
+            EngineError::NoFittingKey => write!(f, "You don't have a key that fits."),
+            EngineError::RequirementsNotMet { required, actual } => write!(
+                f,
+                "You don't meet the requirements to go through this door. It needs {} attack and {} health, whereas you only have {} attack and {} health.",
+                required.attack, required.health, actual.attack, actual.health
+            ),
+            EngineError::NoSuchItem => write!(f, "I find no such item"),
+            EngineError::NoSuchKey => write!(f, "I find no such key"),
+            EngineError::DontHaveItem => write!(f, "You don't have that item."),
+            EngineError::UnknownAction => write!(f, "You can't do that in this fight."),
+            EngineError::AwaitingAnswer => write!(f, "You need to answer first."),
+            EngineError::NothingToAnswer => write!(f, "Nobody asked you anything."),
+            EngineError::GameOver => write!(f, "The game is over."),
+            EngineError::SaveFailed(err) => write!(f, "Could not save the game: {}", err),
+            EngineError::LoadFailed(err) => write!(f, "Could not load savegame: {}", err),
+            EngineError::SaveOver => write!(f, "That adventure is already over."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BattleStage {
+    ChooseAction,
+    ChooseItem,
+}
+
+#[derive(Debug, Clone)]
+struct Battle {
+    door: Door,
+    enemy: Enemy,
+    enemy_health: i32,
+    player_attack: i32,
+    enemy_attack: i32,
+    /// The player's stats before the battle, which they go back to after it.
+    initial: Stats,
+    stage: BattleStage,
+    revived: bool,
+    /// The player's skills, with the uses they have left.
+    skills: Vec<Skill>,
+    /// Rounds left of the player defending, counting this one.
+    defending: u32,
+    /// Starting at 1.
+    round: u32,
+    /// How many times the enemy has healed itself without an item.
+    heals: u32,
+    enraged: bool,
+}
+
+impl Battle {
+    fn situation(&self, player: &Player) -> Situation {
+        Situation {
+            round: self.round,
+            health: self.enemy_health,
+            max_health: self.enemy.health,
+            heals: self.heals,
+            enemy: Stats {
+                health: self.enemy_health,
+                attack: self.enemy_attack,
+                defense: self.enemy.defense,
+            },
+            player: self.player_stats(player),
+        }
+    }
+
+    /// The player's stats this round, counting an attack roll and defending.
+    fn player_stats(&self, player: &Player) -> Stats {
+        Stats {
+            attack: self.player_attack,
+            defense: if self.defending > 0 {
+                percent_of(player.defense, DEFEND_PERCENT.into())
+            } else {
+                player.defense
+            },
+            ..player.stats()
+        }
+    }
+}
+
+impl fmt::Display for BattleAction {
+    /// What to answer to choose the action.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BattleAction::Attack => write!(f, "attack"),
+            BattleAction::Defend => write!(f, "defend"),
+            BattleAction::UseItem => write!(f, "item"),
+            BattleAction::Flee => write!(f, "flee"),
+            BattleAction::Skill(skill) => write!(f, "{}", skill.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pending {
+    Riddle { riddle: Riddle, pickup: Pickup },
+    Battle(Box<Battle>),
+    Loot(Vec<Pickup>),
+}
+
+/// A headless game: feed it [`Command`]s, get back [`Event`]s.
+pub struct Engine {
+    player: Player,
+    pending: Option<Pending>,
+    rng: GameRng,
+    riddles: RiddlePack,
+    win: Option<WinCondition>,
+    lose: Option<LoseCondition>,
+    /// The rooms as the map has them, which saves are stored as changes to.
+    map: Option<HashMap<String, Room>>,
+    saves: SaveManager,
+    /// How many autosaves to keep. None are made if this is 0.
+    backups: usize,
+    rules: Rules,
+    /// Set on entering a room or finishing a battle, so the game is autosaved once the command
+    /// is done.
+    autosave_due: bool,
+}
+
+impl Engine {
+    /// Picks up the player's saved random state, or seeds a fresh one if it has none.
+    pub fn new(mut player: Player) -> Engine {
+        let rng = player.rng.take().unwrap_or_else(GameRng::from_entropy);
+
+        Engine {
+            player,
+            pending: None,
+            rng,
+            riddles: RiddlePack::builtin(),
+            win: None,
+            lose: None,
+            map: None,
+            saves: SaveManager::default(),
+            backups: 0,
+            rules: Rules::default(),
+            autosave_due: false,
+        }
+    }
+
+    /// Sets the riddles that guard items and keys.
+    pub fn with_riddles(mut self, riddles: RiddlePack) -> Engine {
+        self.riddles = riddles;
+        self
+    }
+
+    /// Sets how the game is won and lost. Without these it goes on forever.
+    pub fn with_conditions(
+        mut self,
+        win: Option<WinCondition>,
+        lose: Option<LoseCondition>,
+    ) -> Engine {
+        self.win = win;
+        self.lose = lose;
+        self
+    }
+
+    /// Sets how damage is worked out in battles.
+    pub fn with_rules(mut self, rules: Rules) -> Engine {
+        self.rules = rules;
+        self
+    }
+
+    /// Sets the map the game is played on, so saves only need to store what's changed.
+    pub fn with_map(mut self, rooms: HashMap<String, Room>) -> Engine {
+        self.map = Some(rooms);
+        self
+    }
+
+    /// Sets where [`Command::Save`] writes to.
+    pub fn with_saves(mut self, saves: SaveManager) -> Engine {
+        self.saves = saves;
+        self
+    }
+
+    /// Autosaves on entering a room and after each battle, keeping the last `backups` of them.
+    pub fn with_autosave(mut self, backups: usize) -> Engine {
+        self.backups = backups;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn player_mut(&mut self) -> &mut Player {
+        &mut self.player
+    }
+
+    /// Whether the next command must be a [`Command::Answer`].
+    pub fn is_waiting(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Whether the game has been won or lost.
+    pub fn is_over(&self) -> bool {
+        self.player.ending.is_some()
+    }
+
+    pub fn handle(&mut self, command: Command) -> Vec<Event> {
+        let mut events = vec![];
+
+        if self.is_over() {
+            match command {
+                Command::Save => self.save(&mut events),
+                _ => events.push(Event::Error(EngineError::GameOver)),
+            }
+            return events;
+        }
+
+        self.dispatch(command, &mut events);
+        self.check_ending(&mut events);
+
+        // a game that's just ended keeps its backups from before the end
+        if self.autosave_due && self.pending.is_none() && !self.is_over() {
+            self.autosave_due = false;
+            self.autosave(&mut events);
+        }
+
+        events
+    }
+
+    fn dispatch(&mut self, command: Command, events: &mut Vec<Event>) {
+        if let Command::Answer(answer) = &command {
+            match self.pending.take() {
+                Some(pending) => self.answer(pending, answer.trim(), events),
+                None => events.push(Event::Error(EngineError::NothingToAnswer)),
+            }
+            return;
+        }
+
+        if self.pending.is_some() {
+            events.push(Event::Error(EngineError::AwaitingAnswer));
+            return;
+        }
+
+        if let Command::Load(name) = &command {
+            self.load(name, events);
+            return;
+        }
+
+        // only moves that go through take a turn
+        let is_move = matches!(
+            command,
+            Command::Go(_)
+                | Command::Take(_)
+                | Command::TakeKey(_)
+                | Command::Use(_)
+                | Command::Unlock(_)
+                | Command::Lock(_)
+        );
+        let start = events.len();
+
+        match command {
+            Command::Answer(_) | Command::Load(_) => {}
+            Command::Look => {
+                events.push(Event::RoomDescription(
+                    self.player.room().description.clone(),
+                ));
+            }
+            Command::Go(door_name) => self.go(&door_name, events),
+            Command::Take(item_name) => self.take_item(&item_name, events),
+            Command::TakeKey(key_name) => self.take_key(&key_name, events),
+            Command::Use(item_name) => self.use_half(&item_name, events),
+            Command::Unlock(door_name) => self.unlock(&door_name, events),
+            Command::Lock(door_name) => self.lock(&door_name, events),
+            Command::Search => self.search(events),
+            Command::Battles => events.push(Event::Battles(self.player.battles.clone())),
+            Command::Inventory => events.push(Event::Inventory {
+                items: self.player.items_held.clone(),
+                keys: self.player.keys_held.clone(),
+            }),
+            Command::Stats => events.push(Event::Stats {
+                stats: self.player.stats(),
+                class: self.player.class.name.clone(),
+            }),
+            Command::Save => self.save(events),
+        }
+
+        if is_move && !events[start..].iter().any(|e| matches!(e, Event::Error(_))) {
+            self.tick(events);
+        }
+    }
+
+    /// Ends the game once the win or lose condition is met, but never part way through a battle
+    /// or riddle.
+    fn check_ending(&mut self, events: &mut Vec<Event>) {
+        if self.pending.is_some() {
+            return;
+        }
+
+        let ending = if self.win.as_ref().is_some_and(|w| w.is_met(&self.player)) {
+            Ending::Won
+        } else if self.lose.as_ref().is_some_and(|l| l.is_met(&self.player)) {
+            Ending::Lost
+        } else {
+            return;
+        };
+
+        self.player.ending = Some(ending);
+        events.push(Event::GameOver(self.player.summary()));
+
+        if ending == Ending::Lost && self.player.permadeath {
+            self.end_saves(events);
+        }
+    }
+
+    /// With permadeath, there's no going back to a save or backup from before the end.
+    fn end_saves(&mut self, events: &mut Vec<Event>) {
+        if let Err(err) = self.saves.delete_backups(self.save_name(), self.backups) {
+            events.push(Event::Error(EngineError::SaveFailed(err.to_string())));
+        }
+
+        // a game that was never saved has nothing to mark
+        if !self.saves.path(self.save_name()).exists() {
+            return;
+        }
+
+        let save = self.save_file();
+
+        match self.saves.save(self.save_name(), &save) {
+            Ok(path) => events.push(Event::SaveEnded(path.display().to_string())),
+            Err(err) => events.push(Event::Error(EngineError::SaveFailed(err.to_string()))),
+        }
+    }
+
+    fn answer(&mut self, pending: Pending, answer: &str, events: &mut Vec<Event>) {
+        match pending {
+            Pending::Riddle { riddle, pickup } => {
+                if AnswerMatching::default().normalise(answer) == "hint" {
+                    if let Some(hint) = &riddle.hint {
+                        events.push(Event::Hint(hint.clone()));
+                        self.pending = Some(Pending::Riddle { riddle, pickup });
+                        return;
+                    }
+                }
+
+                let correct = self.riddles.is_correct(&riddle, answer);
+                events.push(Event::RiddleAnswered(correct));
+                self.claim(pickup, correct, events);
+            }
+            Pending::Battle(battle) => self.battle_turn(*battle, answer, events),
+            Pending::Loot(loot) => self.pick_loot(loot, answer, events),
+        }
+    }
+
+    fn go(&mut self, door_name: &str, events: &mut Vec<Event>) {
+        let door = match self
+            .player
+            .room()
+            .doors
+            .iter()
+            .find(|d| d.name == door_name)
+        {
+            Some(door) => door.clone(),
+            None => {
+                events.push(Event::Error(EngineError::NoSuchDoor));
+                return;
+            }
+        };
+
+        let keys = if door.locked {
+            let Some(keys) = door
+                .key
+                .as_ref()
+                .and_then(|lock| self.player.keys_for(lock))
+            else {
+                events.push(Event::Error(EngineError::DoorLocked));
+                return;
+            };
+
+            Some(keys)
+        } else {
+            None
+        };
+
+        // a single-use key is only used up once the player can actually go through
+        if let Some(requirements) = &door.requirements {
+            if self.player.health < requirements.health || self.player.attack < requirements.attack
+            {
+                events.push(Event::Error(EngineError::RequirementsNotMet {
+                    required: Stats {
+                        health: requirements.health,
+                        attack: requirements.attack,
+                        defense: requirements.defense,
+                    },
+                    actual: self.player.stats(),
+                }));
+                return;
+            }
+        }
+
+        if let Some(keys) = keys {
+            self.open(&door.name, keys, events);
+        }
+
+        if self.is_guarded(&door) {
+            self.start_battle(door, events);
+            return;
+        }
+
+        self.enter(&door, events);
+    }
+
+    fn unlock(&mut self, door_name: &str, events: &mut Vec<Event>) {
+        let Some(door) = self.find_door(door_name) else {
+            events.push(Event::Error(EngineError::NoSuchDoor));
+            return;
+        };
+
+        if !door.locked {
+            events.push(Event::Error(EngineError::NotLocked));
+            return;
+        }
+
+        match door
+            .key
+            .as_ref()
+            .and_then(|lock| self.player.keys_for(lock))
+        {
+            Some(keys) => self.open(door_name, keys, events),
+            None => events.push(Event::Error(EngineError::NoFittingKey)),
+        }
+    }
+
+    fn lock(&mut self, door_name: &str, events: &mut Vec<Event>) {
+        let Some(door) = self.find_door(door_name) else {
+            events.push(Event::Error(EngineError::NoSuchDoor));
+            return;
+        };
+
+        let Some(lock) = &door.key else {
+            events.push(Event::Error(EngineError::NoLock));
+            return;
+        };
+
+        if door.locked {
+            events.push(Event::Error(EngineError::AlreadyLocked));
+        } else if self.player.keys_for(lock).is_none() {
+            events.push(Event::Error(EngineError::NoFittingKey));
+        } else {
+            self.set_locked(door_name, true);
+            events.push(Event::Locked(door_name.to_string()));
+        }
+    }
+
+    fn find_door(&self, door_name: &str) -> Option<Door> {
+        self.player
+            .room()
+            .doors
+            .iter()
+            .find(|d| d.name == door_name)
+            .cloned()
+    }
+
+    /// Unlocks a door in the current room for good, with the keys at `keys` in `keys_held`.
+    fn open(&mut self, door_name: &str, mut keys: Vec<usize>, events: &mut Vec<Event>) {
+        self.set_locked(door_name, false);
+
+        events.push(Event::Unlocked {
+            door: door_name.to_string(),
+            keys: keys
+                .iter()
+                .map(|&i| self.player.keys_held[i].clone())
+                .collect(),
+        });
+
+        // from the back, so the positions still line up
+        keys.sort();
+
+        for i in keys.into_iter().rev() {
+            if self.player.keys_held[i].single_use {
+                let key = self.player.keys_held.remove(i);
+                events.push(Event::KeyUsedUp(key));
+            }
+        }
+    }
+
+    fn set_locked(&mut self, door_name: &str, locked: bool) {
+        let room = self.player.map.get_mut(&self.player.current_room).unwrap();
+
+        if let Some(door) = room.doors.iter_mut().find(|d| d.name == door_name) {
+            door.locked = locked;
+        }
+    }
+
+    fn is_guarded(&self, door: &Door) -> bool {
+        match &door.enemy {
+            Some(enemy) => !self
+                .player
+                .battles
+                .iter()
+                .any(|b| b.winner && b.enemy_name == enemy.name),
+            None => false,
+        }
+    }
+
+    fn enter(&mut self, door: &Door, events: &mut Vec<Event>) {
+        self.player.current_room = door.associated_room_name.clone();
+
+        events.push(Event::RoomEntered(self.player.current_room.clone()));
+        self.autosave_due = true;
+    }
+
+    fn start_battle(&mut self, door: Door, events: &mut Vec<Event>) {
+        let enemy = door.enemy.clone().unwrap();
+
+        let player_attack = self.rules.roll(&mut self.rng, self.player.attack);
+        let enemy_attack = self.rules.roll(&mut self.rng, enemy.attack);
+
+        events.push(Event::BattleStarted {
+            player: Stats {
+                attack: player_attack,
+                ..self.player.stats()
+            },
+            enemy: Stats {
+                attack: enemy_attack,
+                ..enemy.stats()
+            },
+            class: self.player.class.name.clone(),
+            items: self.player.items_held.clone(),
+        });
+
+        say(&enemy, &enemy.dialogue.greeting, events);
+
+        let battle = Battle {
+            door,
+            enemy_health: enemy.health,
+            enemy,
+            player_attack,
+            enemy_attack,
+            initial: self.player.stats(),
+            stage: BattleStage::ChooseAction,
+            revived: false,
+            skills: self.player.class.skills.clone(),
+            defending: 0,
+            round: 1,
+            heals: 0,
+            enraged: false,
+        };
+
+        self.next_round(battle, events);
+    }
+
+    fn next_round(&mut self, battle: Battle, events: &mut Vec<Event>) {
+        events.push(Event::BattleRound {
+            player: battle.player_stats(&self.player),
+            enemy: Stats {
+                health: battle.enemy_health,
+                attack: battle.enemy_attack,
+                defense: battle.enemy.defense,
+            },
+        });
+
+        self.choose_action(battle, events);
+    }
+
+    fn choose_action(&mut self, mut battle: Battle, events: &mut Vec<Event>) {
+        battle.stage = BattleStage::ChooseAction;
+
+        events.push(Event::Prompt(Prompt::ChooseAction(self.actions(&battle))));
+        self.pending = Some(Pending::Battle(Box::new(battle)));
+    }
+
+    /// What the player can do this round.
+    fn actions(&self, battle: &Battle) -> Vec<BattleAction> {
+        let mut actions = vec![BattleAction::Attack, BattleAction::Defend];
+
+        if !self.player.items_held.is_empty() {
+            actions.push(BattleAction::UseItem);
+        }
+
+        actions.push(BattleAction::Flee);
+        actions.extend(
+            battle
+                .skills
+                .iter()
+                .filter(|skill| skill.uses > 0)
+                .cloned()
+                .map(BattleAction::Skill),
+        );
+
+        actions
+    }
+
+    fn battle_turn(&mut self, mut battle: Battle, answer: &str, events: &mut Vec<Event>) {
+        let action = match battle.stage {
+            BattleStage::ChooseAction => {
+                match self
+                    .actions(&battle)
+                    .into_iter()
+                    .find(|action| action.to_string() == answer)
+                {
+                    Some(BattleAction::UseItem) => {
+                        battle.stage = BattleStage::ChooseItem;
+                        events.push(Event::Prompt(Prompt::ChooseItem(
+                            self.player.items_held.clone(),
+                        )));
+                        self.pending = Some(Pending::Battle(Box::new(battle)));
+                        return;
+                    }
+                    Some(action) => action,
+                    None => {
+                        events.push(Event::Error(EngineError::UnknownAction));
+                        self.choose_action(battle, events);
+                        return;
+                    }
+                }
+            }
+            BattleStage::ChooseItem => {
+                match self.player.items_held.iter().find(|i| i.name == answer) {
+                    Some(item) => {
+                        let item = item.clone();
+                        events.push(Event::ItemUsed(item.clone()));
+                        self.player.use_item(item);
+                        BattleAction::UseItem
+                    }
+                    None => {
+                        events.push(Event::Error(EngineError::DontHaveItem));
+                        self.choose_action(battle, events);
+                        return;
+                    }
+                }
+            }
+        };
+
+        self.play_round(battle, action, events);
+    }
+
+    /// Carries out the player's `action`, then the enemy's turn, then the strikes.
+    fn play_round(&mut self, mut battle: Battle, action: BattleAction, events: &mut Vec<Event>) {
+        // a percentage of the usual damage, and whether it ignores defense
+        let mut strike = None;
+        let mut stunned = false;
+
+        match &action {
+            BattleAction::Attack => strike = Some((100, false)),
+            BattleAction::Defend => {
+                battle.defending = DEFEND_ROUNDS;
+                events.push(Event::Defending(DEFEND_ROUNDS));
+            }
+            BattleAction::UseItem => {}
+            BattleAction::Flee => {
+                if self.rng.gen_range(0..100) < FLEE_CHANCE {
+                    self.escape(battle, events);
+                    return;
+                }
+
+                events.push(Event::FleeFailed);
+            }
+            BattleAction::Skill(skill) => {
+                if let Some(held) = battle.skills.iter_mut().find(|s| s.name == skill.name) {
+                    held.uses -= 1;
+                }
+
+                events.push(Event::SkillUsed(skill.clone()));
+
+                match skill.effect {
+                    SkillEffect::Strike { percent, pierce } => strike = Some((percent, pierce)),
+                    SkillEffect::Heal { percent } => {
+                        let healed = percent_of(battle.initial.health, percent.into());
+
+                        self.player.health = self.player.health.saturating_add(healed);
+                        events.push(Event::Healed(healed));
+                    }
+                    SkillEffect::Stun => stunned = true,
+                }
+            }
+        }
+
+        let behaviour = battle.enemy.behaviour;
+        // none if the enemy is stunned
+        let mut enemy_action = None;
+        let mut enemy_attack = battle.enemy_attack;
+        let mut enemy_defense = battle.enemy.defense;
+
+        if stunned {
+            events.push(Event::EnemyStunned);
+        } else {
+            let situation = battle.situation(&self.player);
+
+            if let Some(index) = behaviour.pick_item(&situation, &battle.enemy.items, &mut self.rng)
+            {
+                let item = battle.enemy.items.remove(index);
+
+                battle.enemy_health = battle.enemy_health.saturating_add(item.health);
+                battle.enemy.attack = battle.enemy.attack.saturating_add(item.attack);
+                battle.enemy.defense = battle.enemy.defense.saturating_add(item.defense);
+                battle.enemy_attack = battle.enemy_attack.saturating_add(item.attack);
+
+                events.push(Event::EnemyUsedItem(item));
+            }
+
+            let situation = battle.situation(&self.player);
+            let action = behaviour.action(&situation);
+
+            if behaviour.is_enraged(&situation) && !battle.enraged {
+                battle.enraged = true;
+                events.push(Event::EnemyEnraged);
+            }
+
+            enemy_attack = percent_of(
+                battle.enemy_attack,
+                behaviour.attack_percent(&situation).into(),
+            );
+            enemy_defense = percent_of(
+                battle.enemy.defense,
+                behaviour.defense_percent(&situation, action).into(),
+            );
+            enemy_action = Some(action);
+
+            match action {
+                Action::Flee => {
+                    self.flee(battle, events);
+                    return;
+                }
+                Action::Heal(amount) => {
+                    let amount = amount.min(battle.enemy.health - battle.enemy_health);
+
+                    battle.heals += 1;
+                    battle.enemy_health += amount;
+                    events.push(Event::EnemyHealed(amount));
+                }
+                Action::Defend => events.push(Event::EnemyDefended),
+                Action::Attack => {}
+            }
+        }
+
+        let player_defense = battle.player_stats(&self.player).defense;
+        let first_strike = self.player.class.has_first_strike();
+
+        for player_turn in [first_strike, !first_strike] {
+            if player_turn {
+                let Some((percent, pierce)) = strike else {
+                    continue;
+                };
+
+                let defense = if pierce { 0 } else { enemy_defense };
+                self.player_strike(&mut battle, defense, percent, events);
+
+                if battle.enemy_health <= 0 {
+                    self.win(battle, events);
+                    return;
+                }
+            } else if enemy_action == Some(Action::Attack) {
+                self.enemy_strike(enemy_attack, player_defense, events);
+
+                if self.player.health <= 0 {
+                    if !battle.revived && self.player.class.can_revive() {
+                        battle.revived = true;
+                        self.player.health = battle.initial.health;
+                        events.push(Event::Revived);
+                        continue;
+                    }
+
+                    self.lose(battle, events);
+                    return;
+                }
+            }
+        }
+
+        battle.player_attack = self.rules.roll(&mut self.rng, self.player.attack);
+        battle.enemy_attack = self.rules.roll(&mut self.rng, battle.enemy.attack);
+        battle.defending = battle.defending.saturating_sub(1);
+        battle.round += 1;
+
+        self.next_round(battle, events);
+    }
+
+    fn player_strike(
+        &mut self,
+        battle: &mut Battle,
+        enemy_defense: i32,
+        percent: u32,
+        events: &mut Vec<Event>,
+    ) {
+        let hit = self.rules.hit(
+            &mut self.rng,
+            battle.player_attack,
+            enemy_defense,
+            percent,
+            self.player.class.crit(),
+        );
+        let damage = hit.damage;
+
+        if hit.critical {
+            events.push(Event::CriticalHit(damage));
+        }
+
+        battle.enemy_health = battle.enemy_health.saturating_sub(damage);
+
+        let stolen = percent_of(damage, self.player.class.lifesteal_percent().into());
+
+        if stolen > 0 {
+            self.player.health = self.player.health.saturating_add(stolen);
+            events.push(Event::Lifesteal(stolen));
+        }
+    }
+
+    fn enemy_strike(&mut self, enemy_attack: i32, player_defense: i32, events: &mut Vec<Event>) {
+        let dodge_chance = self.player.class.dodge_chance();
+
+        if dodge_chance > 0 && self.rng.gen_range(0..100) < dodge_chance {
+            events.push(Event::Dodged);
+            return;
+        }
+
+        let hit = self
+            .rules
+            .hit(&mut self.rng, enemy_attack, player_defense, 100, None);
+
+        if hit.critical {
+            events.push(Event::EnemyCriticalHit(hit.damage));
+        }
+
+        self.player.health = self.player.health.saturating_sub(hit.damage);
+    }
+
+    /// Puts the player's stats back to how they were before the battle.
+    fn reset(&mut self, battle: &Battle) {
+        self.player.health = battle.initial.health;
+        self.player.attack = battle.initial.attack;
+        self.player.defense = battle.initial.defense;
+    }
+
+    fn win(&mut self, battle: Battle, events: &mut Vec<Event>) {
+        self.reset(&battle);
+
+        say(&battle.enemy, &battle.enemy.dialogue.defeated, events);
+
+        let result = BattleResult::new(true, self.player.health, 0, battle.enemy.name.clone());
+        self.player.battles.push(result.clone());
+
+        // the enemy as it was before the fight, without any items it used
+        let enemy = battle.door.enemy.as_ref().unwrap_or(&battle.enemy);
+        let class = &self.player.class;
+        self.player.health = self
+            .player
+            .health
+            .saturating_add(enemy.health)
+            .saturating_add(class.won_battle_health_bonus);
+        self.player.attack = self
+            .player
+            .attack
+            .saturating_add(enemy.attack)
+            .saturating_add(class.won_battle_attack_bonus);
+        self.player.defense = self
+            .player
+            .defense
+            .saturating_add(enemy.defense)
+            .saturating_add(class.won_battle_defense_bonus);
+
+        events.push(Event::BattleWon(result));
+
+        let loot = self.drop_loot(&battle);
+
+        self.enter(&battle.door, events);
+        self.offer_loot(loot, events);
+    }
+
+    /// Everything `battle`'s enemy drops, which is taken off the enemy in the map so it can't be
+    /// dropped again.
+    fn drop_loot(&mut self, battle: &Battle) -> Vec<Pickup> {
+        let enemy = &battle.enemy;
+
+        let mut loot: Vec<Pickup> = enemy
+            .items
+            .iter()
+            .chain(&enemy.loot.items)
+            .cloned()
+            .map(Pickup::Item)
+            .chain(
+                enemy
+                    .keys
+                    .iter()
+                    .chain(&enemy.loot.keys)
+                    .cloned()
+                    .map(Pickup::Key),
+            )
+            .collect();
+
+        for drop in &enemy.loot.random {
+            if self.rng.gen_range(0..100) < drop.chance {
+                loot.push(drop.pickup.clone());
+            }
+        }
+
+        let room = self.player.map.get_mut(&self.player.current_room).unwrap();
+
+        if let Some(enemy) = room
+            .doors
+            .iter_mut()
+            .find(|d| d.name == battle.door.name)
+            .and_then(|d| d.enemy.as_mut())
+        {
+            enemy.take_loot();
+        }
+
+        loot
+    }
+
+    fn offer_loot(&mut self, loot: Vec<Pickup>, events: &mut Vec<Event>) {
+        if loot.is_empty() {
+            return;
+        }
+
+        events.push(Event::Prompt(Prompt::Loot(loot.clone())));
+        self.pending = Some(Pending::Loot(loot));
+    }
+
+    fn pick_loot(&mut self, mut loot: Vec<Pickup>, answer: &str, events: &mut Vec<Event>) {
+        let taken = match answer {
+            "all" => std::mem::take(&mut loot),
+            "leave" => {
+                let room = self.player.map.get_mut(&self.player.current_room).unwrap();
+
+                for pickup in &loot {
+                    match pickup {
+                        Pickup::Item(item) => room.items.push(item.clone()),
+                        Pickup::Key(key) => room.keys.push(key.clone()),
+                    }
+                }
+
+                events.push(Event::LootLeft(loot));
+                return;
+            }
+            _ => match loot.iter().position(|p| p.name() == answer) {
+                Some(i) => vec![loot.remove(i)],
+                None => {
+                    events.push(Event::Error(EngineError::NoSuchItem));
+                    vec![]
+                }
+            },
+        };
+
+        for pickup in taken {
+            match pickup {
+                Pickup::Item(item) => {
+                    self.player.items_held.push(item.clone());
+                    events.push(Event::ItemTaken(item));
+                }
+                Pickup::Key(key) => {
+                    self.player.keys_held.push(key.clone());
+                    events.push(Event::KeyTaken(key));
+                }
+            }
+        }
+
+        self.offer_loot(loot, events);
+    }
+
+    /// The enemy ran off, so the player goes through the door, but doesn't gain its stats.
+    fn flee(&mut self, battle: Battle, events: &mut Vec<Event>) {
+        self.reset(&battle);
+
+        let result = BattleResult {
+            fled: true,
+            ..BattleResult::new(
+                true,
+                self.player.health,
+                battle.enemy_health,
+                battle.enemy.name.clone(),
+            )
+        };
+        self.player.battles.push(result.clone());
+
+        events.push(Event::EnemyFled(result));
+        self.enter(&battle.door, events);
+    }
+
+    /// The player got away, back into the room they came from. The door stays guarded.
+    fn escape(&mut self, battle: Battle, events: &mut Vec<Event>) {
+        self.reset(&battle);
+
+        events.push(Event::Escaped(self.player.current_room.clone()));
+        self.autosave_due = true;
+    }
+
+    fn lose(&mut self, battle: Battle, events: &mut Vec<Event>) {
+        say(&battle.enemy, &battle.enemy.dialogue.victorious, events);
+
+        let result = BattleResult::new(false, 0, battle.enemy_health, battle.enemy.name.clone());
+        self.player.battles.push(result.clone());
+
+        self.reset(&battle);
+
+        events.push(Event::BattleLost(result));
+        self.autosave_due = true;
+    }
+
+    fn ask_question(&mut self, pickup: Pickup, events: &mut Vec<Event>) {
+        let riddle = match &pickup {
+            Pickup::Item(item) => self.riddles.choose_for_item(&item.name, &mut self.rng),
+            Pickup::Key(key) => self.riddles.choose_for_key(&key.name, &mut self.rng),
+        }
+        .clone();
+
+        events.push(Event::Prompt(Prompt::Riddle(riddle.clone())));
+        self.pending = Some(Pending::Riddle { riddle, pickup });
+    }
+
+    fn claim(&mut self, pickup: Pickup, correct: bool, events: &mut Vec<Event>) {
+        let room = self.player.current_room.clone();
+        let id = pickup.id(&room);
+
+        if correct {
+            self.player.riddle_failures.remove(&id);
+            self.remove_pickup(&room, &pickup);
+
+            match pickup {
+                Pickup::Item(item) => {
+                    self.player.items_held.push(item.clone());
+                    events.push(Event::ItemTaken(item));
+                }
+                Pickup::Key(key) => {
+                    self.player.keys_held.push(key.clone());
+                    events.push(Event::KeyTaken(key));
+                }
+            }
+            return;
+        }
+
+        let policy = self.riddles.on_wrong_answer.clone();
+
+        if policy.health_cost > 0 {
+            // a wrong answer hurts, but never kills
+            self.player.health = (self.player.health - policy.health_cost).max(1);
+            events.push(Event::RiddlePenalty(policy.health_cost));
+        }
+
+        let failures = self.player.riddle_failures.entry(id.clone()).or_insert(0);
+        *failures += 1;
+
+        if *failures < policy.attempts {
+            events.push(Event::TryAgain(policy.attempts - *failures));
+            return;
+        }
+
+        self.player.riddle_failures.remove(&id);
+
+        if let Pickup::Key(key) = &pickup {
+            if !policy.despawn_keys {
+                events.push(Event::KeyKept(key.clone()));
+                return;
+            }
+        }
+
+        self.remove_pickup(&room, &pickup);
+
+        match &pickup {
+            Pickup::Item(item) => events.push(Event::ItemDespawned(item.clone())),
+            Pickup::Key(key) => events.push(Event::KeyDespawned(key.clone())),
+        }
+
+        if let Some(turns) = policy.respawn_after {
+            self.player.despawned.push(Despawned {
+                room,
+                pickup,
+                returns_on_turn: self.player.turn + turns,
+            });
+            events.push(Event::WillRespawn(turns));
+        }
+    }
+
+    fn remove_pickup(&mut self, room: &str, pickup: &Pickup) {
+        let room = self.player.map.get_mut(room).unwrap();
+
+        match pickup {
+            Pickup::Item(item) => room.items.retain(|i| i.name != item.name),
+            Pickup::Key(key) => room.keys.retain(|k| k.name != key.name),
+        }
+    }
+
+    /// Moves the clock on a turn, bringing back anything whose cooldown is up.
+    fn tick(&mut self, events: &mut Vec<Event>) {
+        self.player.turn += 1;
+
+        let turn = self.player.turn;
+        let (back, still_gone) = self
+            .player
+            .despawned
+            .drain(..)
+            .partition(|d| d.returns_on_turn <= turn);
+        self.player.despawned = still_gone;
+
+        for despawned in back {
+            let Despawned { room, pickup, .. } = despawned;
+
+            if let Some(target) = self.player.map.get_mut(&room) {
+                match &pickup {
+                    Pickup::Item(item) => target.items.push(item.clone()),
+                    Pickup::Key(key) => target.keys.push(key.clone()),
+                }
+            }
+
+            events.push(Event::Respawned { pickup, room });
+        }
+    }
+
+    fn take_item(&mut self, item_name: &str, events: &mut Vec<Event>) {
+        match self
+            .player
+            .room()
+            .items
+            .iter()
+            .find(|i| i.name == item_name)
+        {
+            Some(item) => self.ask_question(Pickup::Item(item.clone()), events),
+            None => events.push(Event::Error(EngineError::NoSuchItem)),
+        }
+    }
+
+    fn take_key(&mut self, key_name: &str, events: &mut Vec<Event>) {
+        match self.player.room().keys.iter().find(|k| k.name == key_name) {
+            Some(key) => self.ask_question(Pickup::Key(key.clone()), events),
+            None => events.push(Event::Error(EngineError::NoSuchKey)),
+        }
+    }
+
+    fn use_half(&mut self, item_name: &str, events: &mut Vec<Event>) {
+        match self.player.items_held.iter().find(|i| i.name == item_name) {
+            Some(item) => {
+                let half = Item::new(
+                    item.name.clone(),
+                    item.description.clone(),
+                    item.health / 2,
+                    item.attack / 2,
+                    item.defense / 2,
+                );
+
+                self.player.use_item(half.clone());
+                self.player.items_held.retain(|i| i.name != item_name);
+
+                events.push(Event::ItemUsed(half));
+            }
+            None => events.push(Event::Error(EngineError::DontHaveItem)),
+        }
+    }
+
+    fn search(&self, events: &mut Vec<Event>) {
+        for item in &self.player.room().items {
+            events.push(Event::ItemSeen(item.clone()));
+        }
+
+        for key in &self.player.room().keys {
+            events.push(Event::KeySeen(key.clone()));
+        }
+
+        for door in &self.player.room().doors {
+            events.push(Event::DoorSeen {
+                name: door.name.clone(),
+                locked: door.locked,
+                guarded: self.is_guarded(door),
+            });
+        }
+    }
+
+    /// The name the game is saved under. Games without a name share the save gext always fell
+    /// back to.
+    fn save_name(&self) -> &str {
+        match self.player.game_name.as_str() {
+            "" => "gext",
+            name => name,
+        }
+    }
+
+    fn save_file(&mut self) -> SaveFile {
+        self.player.rng = Some(self.rng.clone());
+        let save = SaveFile::new(&self.player, self.map.as_ref());
+        self.player.rng = None;
+
+        save
+    }
+
+    fn save(&mut self, events: &mut Vec<Event>) {
+        let save = self.save_file();
+
+        match self.saves.save(self.save_name(), &save) {
+            Ok(path) => events.push(Event::Saved(path.display().to_string())),
+            Err(err) => events.push(Event::Error(EngineError::SaveFailed(err.to_string()))),
+        }
+    }
+
+    fn autosave(&mut self, events: &mut Vec<Event>) {
+        if self.backups == 0 {
+            return;
+        }
+
+        let save = self.save_file();
+
+        match self.saves.autosave(self.save_name(), &save, self.backups) {
+            Ok(path) => events.push(Event::Autosaved(path.display().to_string())),
+            Err(err) => events.push(Event::Error(EngineError::SaveFailed(err.to_string()))),
+        }
+    }
+
+    fn load(&mut self, name: &str, events: &mut Vec<Event>) {
+        // saves made without the map to hand carry their own copy of it
+        let map = self.map.as_ref().unwrap_or(&self.player.map);
+
+        let (mut player, changed) = match self.saves.load(name).and_then(|save| {
+            let changed = (!save.same_map(map)).then(|| save.game_version.clone());
+
+            save.restore(map).map(|player| (player, changed))
+        }) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                events.push(Event::Error(EngineError::LoadFailed(err.to_string())));
+                return;
+            }
+        };
+
+        if player.ending.is_some() {
+            events.push(Event::Error(EngineError::SaveOver));
+            return;
+        }
+
+        if let Some(game_version) = changed {
+            events.push(Event::MapChanged(game_version));
+        }
+
+        self.rng = player.rng.take().unwrap_or_else(GameRng::from_entropy);
+        self.player = player;
+
+        events.push(Event::Loaded(name.to_string()));
+        events.push(Event::RoomEntered(self.player.current_room.clone()));
+    }
+}
+
+fn say(enemy: &Enemy, line: &Option<String>, events: &mut Vec<Event>) {
+    if let Some(line) = line {
+        events.push(Event::EnemySays {
+            enemy: enemy.name.clone(),
+            line: line.clone(),
+        });
+    }
+}
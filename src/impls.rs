@@ -1,18 +1,115 @@
-use rand::{self, Rng};
-use std::fs::File;
-use std::io::Write;
+use std::fmt;
 
 use std::collections::HashMap;
 
-use console::Term;
-
-use crate::structs::{BattleResult, Class, Door, Item, Key, Player, Room, RoomRequirements};
-
-use crate::write;
+use crate::behaviour::Behaviour;
+use crate::engine::Stats;
+use crate::structs::{
+    Ability, BattleResult, Class, Dialogue, Door, Ending, Enemy, Item, Key, Lock, Loot, Pickup,
+    Player, Room, RoomRequirements, Skill, SkillEffect, Summary,
+};
 
 impl Key {
     pub fn new(name: String) -> Key {
-        Key { name }
+        Key {
+            name,
+            single_use: false,
+            opens: vec![],
+        }
+    }
+
+    /// Whether this key opens a door locked with `lock`.
+    pub fn fits(&self, lock: &Key) -> bool {
+        self.name == lock.name || self.opens.contains(&lock.name)
+    }
+
+    /// Where in `keys` the key to open `lock` is. Keys that aren't used up are picked over
+    /// single-use ones, and then a key made for it over a master key.
+    pub fn find(keys: &[Key], lock: &Key) -> Option<usize> {
+        Key::best(keys, std::slice::from_ref(lock))
+    }
+
+    /// Where in `keys` the best key to open any of `locks` is, as for [`find`](Key::find).
+    fn best(keys: &[Key], locks: &[Key]) -> Option<usize> {
+        locks
+            .iter()
+            .flat_map(|lock| {
+                keys.iter()
+                    .enumerate()
+                    .filter(|(_, k)| k.fits(lock))
+                    .map(|(i, k)| ((k.single_use, k.name != lock.name), i))
+            })
+            .min()
+            .map(|(_, i)| i)
+    }
+}
+
+impl Lock {
+    /// Drops keys with no name, which older maps used to mean no key at all. `None` if that
+    /// leaves no keys.
+    pub fn without_blanks(self) -> Option<Lock> {
+        let named = |keys: Vec<Key>| -> Vec<Key> {
+            keys.into_iter().filter(|k| !k.name.is_empty()).collect()
+        };
+
+        match self {
+            Lock::One(key) if key.name.is_empty() => None,
+            Lock::One(key) => Some(Lock::One(key)),
+            Lock::Any { any } => Some(named(any))
+                .filter(|any| !any.is_empty())
+                .map(|any| Lock::Any { any }),
+            Lock::All { all } => Some(named(all))
+                .filter(|all| !all.is_empty())
+                .map(|all| Lock::All { all }),
+        }
+    }
+
+    /// Every key the lock names.
+    pub fn keys(&self) -> &[Key] {
+        match self {
+            Lock::One(key) => std::slice::from_ref(key),
+            Lock::Any { any } => any,
+            Lock::All { all } => all,
+        }
+    }
+
+    /// Where in `keys` the keys that open the lock are, or `None` if they don't.
+    pub fn find(&self, keys: &[Key]) -> Option<Vec<usize>> {
+        match self {
+            Lock::One(key) => Key::find(keys, key).map(|i| vec![i]),
+            Lock::Any { any } => Key::best(keys, any).map(|i| vec![i]),
+            Lock::All { all } => {
+                let mut used = vec![];
+
+                for key in all {
+                    let i = Key::find(keys, key)?;
+
+                    if !used.contains(&i) {
+                        used.push(i);
+                    }
+                }
+
+                Some(used)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Lock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = |keys: &[Key], joiner: &str| {
+            keys.iter()
+                .map(|k| format!("\"{}\"", k.name))
+                .collect::<Vec<String>>()
+                .join(joiner)
+        };
+
+        match self {
+            Lock::One(key) => write!(f, "the \"{}\" key", key.name),
+            Lock::Any { any } => write!(f, "the {} key", names(any, " or ")),
+            Lock::All { all } if all.len() == 1 => write!(f, "the {} key", names(all, "")),
+            Lock::All { all } => write!(f, "the {} keys", names(all, " and ")),
+        }
     }
 }
 
@@ -34,7 +131,7 @@ impl Door {
         description: String,
         locked: bool,
         key: Key,
-        enemy: Option<Player>,
+        enemy: Option<Enemy>,
         associated_room_name: String,
         requirements: Option<RoomRequirements>,
     ) -> Door {
@@ -42,14 +139,59 @@ impl Door {
             name,
             description,
             locked,
-            key,
+            key: Lock::One(key).without_blanks(),
             enemy,
             associated_room_name,
             requirements,
+            one_way: false,
+            back: None,
         }
     }
 }
 
+impl Enemy {
+    pub fn new(name: String, items: Vec<Item>, health: i32, attack: i32, defense: i32) -> Enemy {
+        Enemy {
+            name,
+            description: String::new(),
+            health,
+            attack,
+            defense,
+            items,
+            keys: vec![],
+            loot: Loot::default(),
+            dialogue: Dialogue::default(),
+            behaviour: Behaviour::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            health: self.health,
+            attack: self.attack,
+            defense: self.defense,
+        }
+    }
+
+    /// Whether beating it drops anything.
+    pub fn has_loot(&self) -> bool {
+        !self.items.is_empty() || !self.keys.is_empty() || !self.loot.is_empty()
+    }
+
+    /// Empties its pockets, once its loot has dropped.
+    pub fn take_loot(&mut self) {
+        self.items.clear();
+        self.keys.clear();
+        self.loot = Loot::default();
+    }
+}
+
+impl Loot {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.keys.is_empty() && self.random.is_empty()
+    }
+}
+
 impl Room {
     pub fn new(
         name: String,
@@ -68,6 +210,50 @@ impl Room {
     }
 }
 
+impl fmt::Display for Ability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ability::ReviveOnce => write!(f, "gets back up once per battle"),
+            Ability::Lifesteal { percent } => write!(f, "steals {}% of damage dealt", percent),
+            Ability::FirstStrike => write!(f, "always strikes first"),
+            Ability::Dodge { chance } => write!(f, "{}% chance to dodge", chance),
+            Ability::Crit { chance, multiplier } => {
+                write!(f, "{}% chance to deal {}% damage", chance, multiplier)
+            }
+        }
+    }
+}
+
+impl Pickup {
+    pub fn name(&self) -> &str {
+        match self {
+            Pickup::Item(item) => &item.name,
+            Pickup::Key(key) => &key.name,
+        }
+    }
+
+    /// Identifies this pickup within a room, e.g. `Kitchen/item/apple`.
+    pub fn id(&self, room: &str) -> String {
+        let kind = match self {
+            Pickup::Item(_) => "item",
+            Pickup::Key(_) => "key",
+        };
+
+        format!("{}/{}/{}", room, kind, self.name())
+    }
+}
+
+impl Skill {
+    pub fn new(name: String, description: String, uses: u32, effect: SkillEffect) -> Skill {
+        Skill {
+            name,
+            description,
+            uses,
+            effect,
+        }
+    }
+}
+
 impl BattleResult {
     pub fn new(
         winner: bool,
@@ -80,12 +266,13 @@ impl BattleResult {
             player_health,
             enemy_health,
             enemy_name,
+            fled: false,
         }
     }
 }
 
-impl Class {
-    pub fn default() -> Class {
+impl Default for Class {
+    fn default() -> Class {
         Class {
             name: "Default".to_string(),
             description: "Looks like somebody didn't choose a class...".to_string(),
@@ -97,43 +284,16 @@ impl Class {
             won_battle_attack_bonus: 5,
             won_battle_defense_bonus: 5,
             won_battle_health_bonus: 5,
-        }
-    }
-    pub fn new(
-        name: String,
-        description: String,
-        health: i32,
-        attack: i32,
-        defense: i32,
-        starting_items: Vec<Item>,
-        starting_keys: Vec<Key>,
-        won_battle_attack_bonus: i32,
-        won_battle_defense_bonus: i32,
-        won_battle_health_bonus: i32,
-    ) -> Class {
-        Class {
-            name,
-            description,
-            health,
-            attack,
-            defense,
-            starting_items,
-            starting_keys,
-            won_battle_attack_bonus,
-            won_battle_defense_bonus,
-            won_battle_health_bonus,
+            abilities: vec![],
+            skills: vec![],
         }
     }
 }
 
-impl Player {
-    pub fn init(
-        name: String,
-        map: HashMap<String, Room>,
-        game_name: String,
-        starting_room: String,
-    ) -> Player {
-        let classes: Vec<Class> = vec![
+impl Class {
+    /// The classes offered when no map or class file provides its own.
+    pub fn builtins() -> Vec<Class> {
+        vec![
             Class::new(
                 "Warrior".to_string(),
                 "A strong and brave warrior.".to_string(),
@@ -151,7 +311,16 @@ impl Player {
                 5,
                 5,
                 5,
-            ),
+            )
+            .with_skills(vec![Skill::new(
+                "cleave".to_string(),
+                "A mighty swing, for double damage.".to_string(),
+                1,
+                SkillEffect::Strike {
+                    percent: 200,
+                    pierce: false,
+                },
+            )]),
             Class::new(
                 "Mage".to_string(),
                 "A wise and powerful mage.".to_string(),
@@ -169,7 +338,16 @@ impl Player {
                 10,
                 0,
                 10,
-            ),
+            )
+            .with_skills(vec![Skill::new(
+                "fireball".to_string(),
+                "A blast of fire that burns straight through armour.".to_string(),
+                2,
+                SkillEffect::Strike {
+                    percent: 100,
+                    pierce: true,
+                },
+            )]),
             Class::new(
                 "Rogue".to_string(),
                 "A sneaky and agile rogue.".to_string(),
@@ -187,7 +365,13 @@ impl Player {
                 0,
                 10,
                 5,
-            ),
+            )
+            .with_skills(vec![Skill::new(
+                "smoke bomb".to_string(),
+                "Vanish in a cloud of smoke, so the enemy loses its next turn.".to_string(),
+                1,
+                SkillEffect::Stun,
+            )]),
             Class::new(
                 "Monk".to_string(),
                 "A peaceful and strong monk.".to_string(),
@@ -205,7 +389,13 @@ impl Player {
                 5,
                 5,
                 5,
-            ),
+            )
+            .with_skills(vec![Skill::new(
+                "meditate".to_string(),
+                "Heal a quarter of your health.".to_string(),
+                2,
+                SkillEffect::Heal { percent: 25 },
+            )]),
             Class::new(
                 "Dark Mage".to_string(),
                 "A mage with no buffs - but has a second shot at every battle.".to_string(),
@@ -217,56 +407,120 @@ impl Player {
                 0,
                 0,
                 0,
-            ),
-        ];
-
-        write("Choose a class:", "yellow");
-
-        for class in &classes {
-            write(
-                format!(
-                    "{}: {} -  {}❤️, {}🪓, {}🛡️",
-                    class.name, class.description, class.health, class.attack, class.defense
-                )
-                .as_str(),
-                "yellow",
-            );
+            )
+            .with_abilities(vec![Ability::ReviveOnce]),
+        ]
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: String,
+        description: String,
+        health: i32,
+        attack: i32,
+        defense: i32,
+        starting_items: Vec<Item>,
+        starting_keys: Vec<Key>,
+        won_battle_attack_bonus: i32,
+        won_battle_defense_bonus: i32,
+        won_battle_health_bonus: i32,
+    ) -> Class {
+        Class {
+            name,
+            description,
+            health,
+            attack,
+            defense,
+            starting_items,
+            starting_keys,
+            won_battle_attack_bonus,
+            won_battle_defense_bonus,
+            won_battle_health_bonus,
+            abilities: vec![],
+            skills: vec![],
         }
+    }
 
-        let input = Term::stdout().read_line().unwrap();
+    pub fn with_abilities(mut self, abilities: Vec<Ability>) -> Class {
+        self.abilities = abilities;
+        self
+    }
+
+    pub fn with_skills(mut self, skills: Vec<Skill>) -> Class {
+        self.skills = skills;
+        self
+    }
 
-        let input = input.trim().to_lowercase();
+    pub fn can_revive(&self) -> bool {
+        self.abilities.contains(&Ability::ReviveOnce)
+    }
 
-        let class = classes
+    pub fn has_first_strike(&self) -> bool {
+        self.abilities.contains(&Ability::FirstStrike)
+    }
+
+    pub fn dodge_chance(&self) -> u32 {
+        self.abilities
             .iter()
-            .find(|c| c.name.to_lowercase() == input)
-            .unwrap_or(&classes[0]);
+            .map(|a| match a {
+                Ability::Dodge { chance } => *chance,
+                _ => 0,
+            })
+            .sum::<u32>()
+            .min(100)
+    }
 
-        write(
-            format!(
-                "You chose the {} class, which has {}❤️, {}🪓 and {}🛡️.",
-                class.name, class.health, class.attack, class.defense
-            )
-            .as_str(),
-            "green",
-        );
+    pub fn lifesteal_percent(&self) -> u32 {
+        self.abilities
+            .iter()
+            .map(|a| match a {
+                Ability::Lifesteal { percent } => *percent,
+                _ => 0,
+            })
+            .sum()
+    }
 
-        let player = Player {
+    /// The chance and multiplier of the class's best critical hit, if it has one.
+    pub fn crit(&self) -> Option<(u32, u32)> {
+        self.abilities
+            .iter()
+            .filter_map(|a| match a {
+                Ability::Crit { chance, multiplier } => Some((*chance, *multiplier)),
+                _ => None,
+            })
+            .max_by_key(|(_, multiplier)| *multiplier)
+    }
+}
+
+impl Player {
+    pub fn init(
+        name: String,
+        map: HashMap<String, Room>,
+        game_name: String,
+        starting_room: String,
+        class: Class,
+    ) -> Player {
+        Player {
             name,
-            map: map.clone(),
+            current_room: starting_room,
+            map,
             items_held: class.starting_items.clone(),
             keys_held: class.starting_keys.clone(),
             health: class.health,
             attack: class.attack,
             defense: class.defense,
             battles: vec![],
-            current_room: map.clone().get(&starting_room).unwrap().clone(),
             game_name,
-            class: class.clone(),
-        };
-
-        player
+            class,
+            rng: None,
+            turn: 0,
+            battles_before_endings: 0,
+            riddle_failures: HashMap::new(),
+            despawned: vec![],
+            ending: None,
+            permadeath: false,
+        }
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         map: HashMap<String, Room>,
@@ -275,7 +529,7 @@ impl Player {
         health: i32,
         attack: i32,
         battles: Vec<BattleResult>,
-        current_room: Room,
+        current_room: String,
         game_name: String,
         defense: i32,
         class: Class,
@@ -292,444 +546,75 @@ impl Player {
             game_name,
             defense,
             class,
+            rng: None,
+            turn: 0,
+            battles_before_endings: 0,
+            riddle_failures: HashMap::new(),
+            despawned: vec![],
+            ending: None,
+            permadeath: false,
         }
     }
-    pub fn fight(&mut self, enemy: &mut Player) -> BattleResult {
-        let initial_enemy = enemy.clone();
-
-        let initial_health = self.health;
-        let initial_attack = self.attack;
-
-        let mut rng = rand::thread_rng();
-
-        let mut player_attack = self.attack + rng.gen_range(-(self.attack / 4)..(self.attack / 4));
-        let mut enemy_attack =
-            enemy.attack + rng.gen_range(-(enemy.attack / 4)..(enemy.attack / 4));
-
-        let enemy_health = enemy.health;
-
-        let mut enemy_health = enemy_health;
 
-        write(
-            format!(
-            "You are in a fight! You have to fight the enemy! You have {}❤️, {}🪓 and {}🛡️, the enemy {}❤️, {}🪓 and {}🛡️. Your class is {}.",
-            self.health, player_attack, self.defense, enemy_health, enemy_attack, enemy.defense, self.class.name
-            ).as_str(),
-            "magenta"
-        );
-
-        for item in &self.items_held {
-            write(
-                format!(
-                    "You have the {}, which buffs you {} health and {} attack",
-                    item.name, item.health, item.attack
-                )
-                .as_str(),
-                "magenta",
-            );
-        }
-
-        write("Fight!", "magenta");
-
-        loop {
-            write(
-                format!(
-                    "You have {}❤️, {}🪓 and {}🛡️, the enemy has {}❤️, {}🪓 and {}🛡️. Do you wish to use an item? (y/n)",
-                    self.health, player_attack, self.defense, enemy_health, enemy_attack, enemy.defense
-                )
-                .as_str(),
-                "magenta"
-            );
-
-            let input = Term::stdout().read_line().unwrap();
-
-            match input.as_str() {
-                "y" => {
-                    write("Which item do you want to use?", "magenta");
-
-                    for item in &self.items_held {
-                        write(
-                            format!(
-                                "You have the {}, which buffs you {}❤️, {}🪓 and {}🛡️",
-                                item.name, item.health, item.attack, item.defense
-                            )
-                            .as_str(),
-                            "magenta",
-                        );
-                    }
-
-                    let input = Term::stdout().read_line().unwrap();
-
-                    let input = input.trim();
-
-                    let item_from_inventory = self.items_held.iter().find(|i| i.name == input);
-
-                    match item_from_inventory {
-                        Some(item) => {
-                            write(
-                                format!(
-                                    "You use the {}, which buffs you {}❤️, {}🪓 and {}🛡️",
-                                    item.name, item.health, item.attack, item.defense
-                                )
-                                .as_str(),
-                                "green",
-                            );
-                            self.use_item(item.clone());
-                        }
-                        None => {
-                            write("You don't have that item.", "red");
-                        }
-                    }
-                }
-
-                _ => {
-                    write("You chose not to use an item.", "magenta");
-                }
-            }
-
-            if enemy.items_held.len() > 0 && rng.gen_bool(0.5) {
-                let item = enemy.items_held[rng.gen_range(0..enemy.items_held.len())].clone();
-
-                write(
-                    format!(
-                        "The enemy used the {}, which buffs them {}❤️, {}🪓 and {}🛡️.",
-                        item.name, item.health, item.attack, item.defense
-                    )
-                    .as_str(),
-                    "green",
-                );
-
-                enemy.use_item(item);
-            }
-
-            self.health -= (enemy_attack - self.defense).max(0);
-
-            if self.health <= 0 {
-                self.battles.push(BattleResult::new(
-                    false,
-                    0,
-                    enemy_health,
-                    enemy.name.clone(),
-                ));
-
-                self.health = initial_health;
-                self.attack = initial_attack;
-
-                if self.class.name == "Dark Mage" {
-                    write("You lost the fight, but you have a second chance! However, you now reassign your class to a different one.", "red");
-                    self.class = Player::init(
-                        self.name.clone(),
-                        self.map.clone(),
-                        self.game_name.clone(),
-                        self.current_room.name.clone(),
-                    )
-                    .class;
-                    return self.fight(&mut initial_enemy.clone());
-                }
-
-                return BattleResult::new(false, 0, enemy_health, enemy.name.clone());
-            }
-
-            enemy_health -= (player_attack - enemy.defense).max(0);
-
-            if enemy_health <= 0 {
-                self.battles
-                    .push(BattleResult::new(true, self.health, 0, enemy.name.clone()));
-
-                self.health = initial_health;
-                self.attack = initial_attack;
-
-                return BattleResult::new(true, self.health, 0, enemy.name.clone());
-            }
-
-            player_attack = self.attack + rng.gen_range(-(self.attack / 4)..(self.attack / 4));
-            enemy_attack = enemy.attack + rng.gen_range(-(enemy.attack / 4)..(enemy.attack / 4));
-        }
+    /// Where in `keys_held` the keys that open `lock` are.
+    pub fn keys_for(&self, lock: &Lock) -> Option<Vec<usize>> {
+        lock.find(&self.keys_held)
     }
 
-    pub fn ask_question(&self) -> bool {
-        let mut questions = HashMap::new();
-
-        questions.insert("There are two ducks in front of a duck, two ducks behind a duck and a duck in the middle. How many ducks are there?".to_string(), vec!["3".to_string(), "three".to_string()]);
-        questions.insert(
-            "What has keys but can't open locks?".to_string(),
-            vec!["piano".to_string()],
-        );
-        questions.insert(
-            "What has a head, a tail, is brown, and has no legs?".to_string(),
-            vec!["penny".to_string()],
-        );
-        questions.insert(
-            "What has a neck but no head?".to_string(),
-            vec!["bottle".to_string()],
-        );
-        questions.insert(
-            "What has a thumb and four fingers but is not alive?".to_string(),
-            vec!["glove".to_string()],
-        );
-        questions.insert(
-            "What has a heart that doesn't beat?".to_string(),
-            vec!["artichoke".to_string()],
-        );
-        questions.insert(
-            "What has a foot but no legs?".to_string(),
-            vec!["snail".to_string()],
-        );
-        questions.insert(
-            "What has a bark but no bite?".to_string(),
-            vec!["tree".to_string()],
-        );
-        questions.insert(
-            "What has a bed but never sleeps?".to_string(),
-            vec!["river".to_string()],
-        );
-        questions.insert(
-            "What has a face and two hands but no arms or legs?".to_string(),
-            vec!["clock".to_string()],
-        );
-        questions.insert(
-            "What has a head and a tail but no body?".to_string(),
-            vec!["coin".to_string()],
-        );
-        questions.insert(
-            "What has a tongue but cannot talk?".to_string(),
-            vec!["shoe".to_string()],
-        );
-        questions.insert(
-            "What has a ring but no finger?".to_string(),
-            vec!["telephone".to_string()],
-        );
-        questions.insert(
-            "Mississippi has two 'p's and four 's's. Now, without using 'p' or 's', spell it."
-                .to_string(),
-            vec!["it".to_string()],
-        );
-        questions.insert(
-            "A man in a car saw a golden door, a silver door, and a bronze door. What door did he open first?".to_string(),
-            vec!["car door".to_string(), "car".to_string()],
-        );
-
-        let mut rng = rand::thread_rng();
-
-        let questions_potential: Vec<&String> = questions.keys().collect::<Vec<&String>>();
-
-        let question = questions_potential[rng.gen_range(0..questions_potential.len())];
-
-        let answers = questions.get(question).unwrap();
-
-        write(question, "yellow");
-
-        let input = Term::stdout().read_line().unwrap();
-
-        let input = input.trim();
-
-        if answers.contains(&input.to_string()) {
-            write("Correct!", "green");
-            return true;
-        } else {
-            write("Incorrect!", "red");
-            return false;
-        }
+    /// The room the player is in.
+    pub fn room(&self) -> &Room {
+        &self.map[&self.current_room]
     }
 
-    pub fn move_through_door(&mut self, door_name: String) {
-        let mut found = false;
-
-        for door in &self.current_room.clone().doors {
-            if door.name == door_name {
-                found = true;
-                if door.locked && !self.keys_held.contains(&door.key) {
-                    write("The door is locked.", "red");
-                    return;
-                } else {
-                    if let Some(requirements) = &door.requirements {
-                        if self.health < requirements.health || self.attack < requirements.attack {
-                            write(format!("You don't meet the requirements to go through this door. It needs {} attack and {} health, whereas you only have {} attack and {} health.", requirements.attack, requirements.health, self.attack, self.health).as_str(), "red");
-                            return;
-                        }
-                    }
-                    if door.enemy.is_some()
-                        && !self
-                            .battles
-                            .iter()
-                            .any(|b| b.enemy_name == door.enemy.clone().unwrap().name)
-                    {
-                        let enemy = &mut door.enemy.clone().unwrap();
-                        let result = self.fight(enemy);
-
-                        if result.winner {
-                            write(
-                                "You won the fight! You gain the enemy's stats they had at the start of the fight.",
-                                "green",
-                            );
-                            self.health += enemy.health + (self.class.won_battle_health_bonus);
-                            self.attack += enemy.attack + (self.class.won_battle_attack_bonus);
-                            self.defense += enemy.defense + (self.class.won_battle_defense_bonus);
-                        } else {
-                            write("You lost the fight, your adventure ends here. :-(", "red");
-                            write(
-                                format!(
-                                    "The enemy had {} health left (you died with {}).",
-                                    result.enemy_health, result.player_health
-                                )
-                                .as_str(),
-                                "red",
-                            );
-                            return;
-                        }
-                    }
-
-                    let old_room = self.current_room.clone();
-
-                    self.current_room = self.map.get(&door.associated_room_name).unwrap().clone();
-
-                    if !self
-                        .current_room
-                        .doors
-                        .iter()
-                        .any(|d| d.associated_room_name == old_room.name)
-                    {
-                        self.current_room.doors.push(Door::new(
-                            old_room.name.clone(),
-                            format!("a door to the {}", old_room.name),
-                            false,
-                            Key::new("".to_string()),
-                            None,
-                            old_room.name.clone(),
-                            None,
-                        ));
-                    }
-
-                    for room in self.map.clone().values() {
-                        for door in &room.doors {
-                            if door.associated_room_name == self.current_room.name
-                                && !self.current_room.doors.iter().any(|d| d.name == room.name)
-                            {
-                                self.current_room.doors.push(Door::new(
-                                    room.name.clone(),
-                                    format!("a door to the {}", room.name),
-                                    false,
-                                    Key::new("".to_string()),
-                                    None,
-                                    room.name.clone(),
-                                    None,
-                                ));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        if !found {
-            write("I find no such door", "red");
-        } else {
-            write(
-                format!("You are in the {}", self.current_room.name).as_str(),
-                "blue",
-            );
+    pub fn stats(&self) -> Stats {
+        Stats {
+            health: self.health,
+            attack: self.attack,
+            defense: self.defense,
         }
     }
 
-    pub fn take_item(&mut self, item_name: String) {
-        let mut found = false;
-        for item in &self.current_room.items {
-            if item.name == item_name {
-                found = true;
-                if self.ask_question() {
-                    self.items_held.push(item.clone());
-                    write(
-                        format!("You took the {} (item)", item.name).as_str(),
-                        "green",
-                    );
-                } else {
-                    write("You got it wrong, the item despawns.", "red");
-                }
-            }
-        }
+    /// Scores the game so far: 1000 for winning, 100 for each battle won and 50 off for each lost,
+    /// 10 for each item and key held, plus the health left, minus one for each turn taken.
+    pub fn summary(&self) -> Summary {
+        let battles_won = self.battles.iter().filter(|b| b.winner).count();
+        let battles_lost = self.battles.len() - battles_won;
 
-        if !found {
-            write("I find no such item", "red");
+        let score = if self.ending == Some(Ending::Won) {
+            1000
+        } else {
+            0
+        } + 100 * battles_won as i64
+            - 50 * battles_lost as i64
+            + 10 * (self.items_held.len() + self.keys_held.len()) as i64
+            + self.health.max(0) as i64
+            - self.turn as i64;
+
+        Summary {
+            ending: self.ending,
+            name: self.name.clone(),
+            class: self.class.name.clone(),
+            turns: self.turn,
+            battles_won,
+            battles_lost,
+            defeated: self
+                .battles
+                .iter()
+                .filter(|b| b.winner && !b.fled)
+                .map(|b| b.enemy_name.clone())
+                .collect(),
+            stats: self.stats(),
+            items: self.items_held.len(),
+            keys: self.keys_held.len(),
+            score: score.max(0),
         }
-
-        self.current_room.items.retain(|i| i.name != item_name);
-        self.map
-            .get_mut(&self.current_room.name)
-            .unwrap()
-            .items
-            .retain(|i| i.name != item_name);
     }
 
     pub fn use_item(&mut self, item: Item) {
-        self.health += item.health;
-        self.attack += item.attack;
+        self.health = self.health.saturating_add(item.health);
+        self.attack = self.attack.saturating_add(item.attack);
+        self.defense = self.defense.saturating_add(item.defense);
 
         self.items_held.retain(|i| i != &item);
     }
-
-    pub fn take_key(&mut self, key_name: String) {
-        let mut found = false;
-        for key in &self.current_room.keys {
-            if key.name == key_name {
-                found = true;
-                if self.ask_question() {
-                    self.keys_held.push(key.clone());
-                    write(format!("You took the {} (key)", key.name).as_str(), "green");
-                } else {
-                    write("You got it wrong, the key despawns.", "red");
-                }
-            }
-        }
-
-        if !found {
-            write("I find no such key", "red");
-        }
-
-        self.current_room.keys.retain(|k| k.name != key_name);
-        self.map
-            .get_mut(&self.current_room.name)
-            .unwrap()
-            .keys
-            .retain(|k| k.name != key_name);
-    }
-
-    pub fn save(&self) {
-        if std::env::args().any(|arg| arg == "--no-save" || arg == "-n") {
-            write(
-                "Not saving the game, this was ran on development mode.",
-                "red",
-            );
-            write("Manual override (y/n)?", "yellow");
-
-            let input = Term::stdout().read_line().unwrap();
-
-            match input.as_str() {
-                "y" => {
-                    write("Saving the game.", "green");
-                }
-                _ => {
-                    write("Not saving the game.", "red");
-                    return;
-                }
-            }
-        }
-
-        let mut file = File::create(format!("savegames/{}.save.json", self.game_name))
-            .unwrap_or_else(|err| {
-                write(
-                    format!(
-                        "Error creating the file: {} (ensure savegames dir exists)",
-                        err.to_string()
-                    )
-                    .as_str(),
-                    "red",
-                );
-
-                File::create("gext.save.json").unwrap()
-            });
-
-        let json = serde_json::to_string(&self).unwrap();
-
-        file.write_all(json.as_bytes()).unwrap();
-    }
 }
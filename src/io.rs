@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+
+use console::{style, Color, Term};
+
+/// Where the game reads player input from and writes its messages to.
+///
+/// Colours are the same names the game has always used: `red`, `green`,
+/// `blue`, `yellow`, `magenta`, `cyan` and `white`.
+pub trait Io {
+    /// Reads one line of input, or `None` once the input has run out.
+    fn read_line(&mut self) -> Option<String>;
+
+    fn write(&mut self, text: &str, color: &str);
+
+    fn prompt(&mut self, text: &str, color: &str) -> Option<String> {
+        self.write(text, color);
+        self.read_line()
+    }
+}
+
+/// Reads from and writes to the real terminal.
+pub struct TermIo {
+    term: Term,
+}
+
+impl TermIo {
+    pub fn new() -> TermIo {
+        TermIo {
+            term: Term::stdout(),
+        }
+    }
+}
+
+impl Default for TermIo {
+    fn default() -> TermIo {
+        TermIo::new()
+    }
+}
+
+impl Io for TermIo {
+    fn read_line(&mut self) -> Option<String> {
+        if self.term.features().is_attended() {
+            return self.term.read_line().ok();
+        }
+
+        // piped input: tell a blank line apart from the end of the input
+        let mut line = String::new();
+
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+        }
+    }
+
+    fn write(&mut self, text: &str, color: &str) {
+        let rgb = match color {
+            "red" => Color::Color256(196),
+            "green" => Color::Color256(46),
+            "blue" => Color::Color256(21),
+            "yellow" => Color::Color256(226),
+            "magenta" => Color::Color256(201),
+            "cyan" => Color::Color256(51),
+            "white" => Color::Color256(231),
+            _ => Color::Color256(21),
+        };
+
+        let styled_text = style(text).fg(rgb);
+
+        self.term.write_line(&styled_text.to_string()).unwrap();
+    }
+}
+
+/// Plays back a fixed list of input lines and keeps everything written.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedIo {
+    input: VecDeque<String>,
+    pub output: Vec<(String, String)>,
+}
+
+impl ScriptedIo {
+    pub fn new<I, S>(input: I) -> ScriptedIo
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedIo {
+            input: input.into_iter().map(Into::into).collect(),
+            output: vec![],
+        }
+    }
+
+    pub fn push_line(&mut self, line: &str) {
+        self.input.push_back(line.to_string());
+    }
+
+    /// The written text, without colours.
+    pub fn lines(&self) -> Vec<&str> {
+        self.output.iter().map(|(text, _)| text.as_str()).collect()
+    }
+}
+
+impl Io for ScriptedIo {
+    fn read_line(&mut self) -> Option<String> {
+        self.input.pop_front()
+    }
+
+    fn write(&mut self, text: &str, color: &str) {
+        self.output.push((text.to_string(), color.to_string()));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptLine {
+    Input(String),
+    Output { text: String, color: String },
+}
+
+/// Wraps another [`Io`] and records both sides of the conversation.
+pub struct TranscriptIo<I: Io> {
+    inner: I,
+    transcript: Vec<TranscriptLine>,
+}
+
+impl<I: Io> TranscriptIo<I> {
+    pub fn new(inner: I) -> TranscriptIo<I> {
+        TranscriptIo {
+            inner,
+            transcript: vec![],
+        }
+    }
+
+    pub fn transcript(&self) -> &[TranscriptLine] {
+        &self.transcript
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// The transcript as plain text, with input lines prefixed by `> `.
+    pub fn to_text(&self) -> String {
+        self.transcript
+            .iter()
+            .map(|line| match line {
+                TranscriptLine::Input(input) => format!("> {}\n", input),
+                TranscriptLine::Output { text, .. } => format!("{}\n", text),
+            })
+            .collect()
+    }
+}
+
+impl<I: Io> Io for TranscriptIo<I> {
+    fn read_line(&mut self) -> Option<String> {
+        let line = self.inner.read_line();
+
+        if let Some(line) = &line {
+            self.transcript.push(TranscriptLine::Input(line.clone()));
+        }
+
+        line
+    }
+
+    fn write(&mut self, text: &str, color: &str) {
+        self.transcript.push(TranscriptLine::Output {
+            text: text.to_string(),
+            color: color.to_string(),
+        });
+        self.inner.write(text, color);
+    }
+}
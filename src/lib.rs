@@ -0,0 +1,18 @@
+pub mod answers;
+pub mod behaviour;
+pub mod client;
+pub mod content;
+pub mod damage;
+pub mod engine;
+pub mod impls;
+pub mod io;
+pub mod map;
+pub mod riddles;
+pub mod rng;
+pub mod save;
+pub mod solve;
+pub mod structs;
+pub mod validate;
+
+pub use engine::{Command, Engine, EngineError, Event, Prompt, Stats};
+pub use io::{Io, ScriptedIo, TermIo, TranscriptIo};
@@ -1,1135 +1,258 @@
-use std::collections::HashMap;
-
-use console::{style, Color, Term};
-use serde_json;
-
-use std::fs::File;
-
-mod impls;
-mod structs;
-
-use structs::{Class, Door, Item, Key, Player, Room, RoomRequirements};
-
-fn write(text: &str, color: &str) {
-    let term = Term::stdout();
-
-    let rgb = match color {
-        "red" => Color::Color256(196),
-        "green" => Color::Color256(46),
-        "blue" => Color::Color256(21),
-        "yellow" => Color::Color256(226),
-        "magenta" => Color::Color256(201),
-        "cyan" => Color::Color256(51),
-        "white" => Color::Color256(231),
-        _ => Color::Color256(21),
-    };
+use std::path::{Path, PathBuf};
+
+use gext::client::{self, Options};
+use gext::content::{
+    load_classes, load_map, load_riddles, map_riddles_path, sibling_riddles_path, Content,
+};
+use gext::io::TermIo;
+use gext::map::Map;
+use gext::riddles::RiddlePack;
+use gext::save::{SaveManager, DEFAULT_BACKUPS};
+use gext::solve::solve;
+use gext::structs::Class;
+use gext::validate::{validate_map, Issue};
+
+fn has_flag(short: &str, long: &str) -> bool {
+    std::env::args().any(|arg| arg == short || arg == long)
+}
 
-    let styled_text = style(text).fg(rgb);
+/// The argument following `short` or `long`, if either was passed.
+fn flag_value<'a>(args: &'a [String], short: &str, long: &str) -> Option<&'a String> {
+    let position = args.iter().position(|arg| arg == short || arg == long)?;
 
-    term.write_line(&styled_text.to_string()).unwrap();
+    Some(args.get(position + 1).unwrap_or_else(|| {
+        eprintln!("Expected a value after {}", args[position]);
+        std::process::exit(1);
+    }))
 }
 
-fn truncate_string(s: &str, n: usize) -> String {
-    if s.len() <= n {
-        return s.to_string();
+/// Flags that are followed by a value, which is part of the flag rather than an argument of its own.
+const VALUE_FLAGS: [&str; 9] = [
+    "-m",
+    "--map",
+    "-c",
+    "--classes",
+    "-r",
+    "--riddles",
+    "--seed",
+    "--save-dir",
+    "--backups",
+];
+
+/// The first argument after the subcommand that isn't a flag, or a flag's value.
+fn positional(args: &[String]) -> Option<&String> {
+    let mut rest = args.iter().skip(2);
+
+    while let Some(arg) = rest.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            rest.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
     }
 
-    let mut new_s = s.to_string();
-    new_s.truncate(n);
-    new_s.push_str("...");
-
-    new_s
+    None
 }
 
-macro_rules! out {
-    ($text:expr, $color:expr) => {
-        write($text, $color)
-    };
+fn load_map_or_exit(path: &str) -> Map {
+    load_map(path).unwrap_or_else(|e| {
+        eprintln!("Could not load map file: {}", e);
+        std::process::exit(1);
+    })
+}
 
-    ($text:expr) => {
-        write($text, "blue")
-    };
+fn load_classes_or_exit(path: &str) -> Vec<Class> {
+    load_classes(path).unwrap_or_else(|e| {
+        eprintln!("Could not load classes file: {}", e);
+        std::process::exit(1);
+    })
 }
 
-fn main() {
-    let term = Term::stdout();
+/// `gext validate [map.json] [-c classes.json] [-r riddles.json]`, in any order: checks a map and exits non-zero
+/// if it has errors, then reports how far each class can get through it.
+fn validate(args: &[String]) {
+    let map_path = positional(args).or_else(|| flag_value(args, "-m", "--map"));
 
-    let empty_room = Room {
-        name: "Empty Room".to_string(),
-        description: "a room with nothing in it".to_string(),
-        doors: vec![],
-        items: vec![],
-        keys: vec![],
+    let map = match map_path {
+        Some(path) => load_map_or_exit(path),
+        None => Map::builtin(),
     };
 
-    let empty_map: HashMap<String, Room> = HashMap::new();
-
-    let mut rooms = HashMap::new();
-
-    rooms.insert(
-        "Kitchen".to_string(),
-        Room::new(
-            "Kitchen".to_string(),
-            "a room with a stove and a fridge".to_string(),
-            vec![
-                Door::new(
-                    "Pantry".to_string(),
-                    "a room with a lot of food".to_string(),
-                    true,
-                    Key::new("pantry".to_string()),
-                    None,
-                    "Pantry".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Dining Room".to_string(),
-                    "a room with a table and chairs".to_string(),
-                    false,
-                    Key::new("dining room".to_string()),
-                    None,
-                    "Dining Room".to_string(),
-                    None,
-                ),
-            ],
-            vec![
-                Item::new("apple".to_string(), "a red apple".to_string(), 10, 0, 0),
-                Item::new("sword".to_string(), "a sharp sword".to_string(), 0, 10, 0),
-            ],
-            vec![Key::new("elf basement".to_string())],
-        ),
-    );
-
-    rooms.insert(
-        "Entrance Hall".to_string(),
-        Room::new(
-            "Entrance Hall".to_string(),
-            "the first room - the entrance hall".to_string(),
-            vec![
-                Door::new(
-                    "Kitchen".to_string(),
-                    "a room with a stove and a fridge".to_string(),
-                    true,
-                    Key::new("kitchen".to_string()),
-                    None,
-                    "Kitchen".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Armory".to_string(),
-                    "a room with a lot of weapons".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Armory".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Concert Hall".to_string(),
-                    "a room with a stage and a lot of seats".to_string(),
-                    true,
-                    Key::new("ticket to the concert hall".to_string()),
-                    None,
-                    "Concert Hall".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Forest".to_string(),
-                    "a room with a lot of trees".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Forest".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Downstairs Staircase".to_string(),
-                    "a staircase leading to the basement".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Downstairs Staircase".to_string(),
-                    None,
-                ),
-            ],
-            vec![
-                Item::new("potion".to_string(), "a red potion".to_string(), 20, 0, 0),
-                Item::new(
-                    "poison vial".to_string(),
-                    "a poisonous liquid that can be used to throw at your enemy".to_string(),
-                    0,
-                    20,
-                    0,
-                ),
-            ],
-            vec![
-                Key::new("kitchen".to_string()),
-                Key::new("concert hall ticket".to_string()),
-            ],
-        ),
-    );
-
-    rooms.insert(
-        "North Dungeon".to_string(),
-        Room::new(
-            "North Dungeon".to_string(),
-            "a dark dungeon".to_string(),
-            vec![],
-            vec![Item::new(
-                "boulder".to_string(),
-                "a boulder".to_string(),
-                50,
-                0,
-                0,
-            )],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "East Dungeon".to_string(),
-        Room::new(
-            "East Dungeon".to_string(),
-            "a dark dungeon".to_string(),
-            vec![Door::new(
-                "East Dungeon Cell".to_string(),
-                "a dark dungeon cell".to_string(),
-                false,
-                Key::new("".to_string()),
-                None,
-                "Dungeon".to_string(),
-                None,
-            )],
-            vec![Item::new(
-                "stick".to_string(),
-                "a stick".to_string(),
-                0,
-                1,
-                0,
-            )],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "West Dungeon".to_string(),
-        Room::new(
-            "West Dungeon".to_string(),
-            "a dark dungeon".to_string(),
-            vec![],
-            vec![Item::new(
-                "stale bread".to_string(),
-                "a loaf of bread".to_string(),
-                -10,
-                0,
-                0,
-            )],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "South Dungeon".to_string(),
-        Room::new(
-            "South Dungeon".to_string(),
-            "a dark dungeon".to_string(),
-            vec![],
-            vec![Item::new(
-                "window bar".to_string(),
-                "a bar".to_string(),
-                0,
-                50,
-                0,
-            )],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "East Dungeon Cell".to_string(),
-        Room::new(
-            "East Dungeon Cell".to_string(),
-            "a dark dungeon cell".to_string(),
-            vec![],
-            vec![Item::new(
-                "chain".to_string(),
-                "a chain".to_string(),
-                0,
-                100,
-                0,
-            )],
-            vec![Key::new("dungeon key".to_string())],
-        ),
-    );
-
-    rooms.insert(
-        "Dungeon Corridor".to_string(),
-        Room::new(
-            "Dungeon Corridor".to_string(),
-            "a dark corridor".to_string(),
-            vec![
-                Door::new(
-                    "North Dungeon".to_string(),
-                    "a dark dungeon".to_string(),
-                    true,
-                    Key::new("dungeon key".to_string()),
-                    None,
-                    "Dungeon".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "East Dungeon".to_string(),
-                    "a dark dungeon".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Dungeon".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "West Dungeon".to_string(),
-                    "a dark dungeon".to_string(),
-                    true,
-                    Key::new("dungeon key".to_string()),
-                    None,
-                    "Dungeon".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "South Dungeon".to_string(),
-                    "a dark dungeon".to_string(),
-                    false,
-                    Key::new("dungeon key".to_string()),
-                    None,
-                    "Dungeon".to_string(),
-                    None,
-                ),
-            ],
-            vec![],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Downstairs Staircase".to_string(),
-        Room::new(
-            "Downstairs Staircase".to_string(),
-            "a staircase leading to the basement".to_string(),
-            vec![Door::new(
-                "Dungeon Corridor".to_string(),
-                "a dark corridor".to_string(),
-                true,
-                Key::new("dungeons".to_string()),
-                None,
-                "Dungeon Corridor".to_string(),
-                None,
-            )],
-            vec![],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Elf Treehouse".to_string(),
-        Room::new(
-            "Elf Treehouse".to_string(),
-            "a treehouse full of elves".to_string(),
-            vec![Door::new(
-                "Elf Basement".to_string(),
-                "a basement full of elves".to_string(),
-                true,
-                Key::new("elf basement".to_string()),
-                Some(Player::new(
-                    "Elf".to_string(),
-                    empty_map.clone(),
-                    vec![],
-                    vec![],
-                    800,
-                    200,
-                    vec![],
-                    empty_room.clone(),
-                    "".to_string(),
-                    100,
-                    Class::default(),
-                )),
-                "Elf Basement".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("elf hat".to_string(), "a hat".to_string(), 0, 10, 0),
-                Item::new(
-                    "elf shoes".to_string(),
-                    "a pair of shoes".to_string(),
-                    10,
-                    0,
-                    0,
-                ),
-            ],
-            vec![Key::new("dungeons".to_string())],
-        ),
-    );
-
-    rooms.insert(
-        "Elf Basement".to_string(),
-        Room::new(
-            "Elf Basement".to_string(),
-            "a basement full of elves".to_string(),
-            vec![],
-            vec![Item::new(
-                "elven scythe".to_string(),
-                "a scythe".to_string(),
-                0,
-                70,
-                0,
-            )],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Forest Cabin".to_string(),
-        Room::new(
-            "Forest Cabin".to_string(),
-            "a little hideaway".to_string(),
-            vec![Door::new(
-                "Elf Treehouse".to_string(),
-                "a treehouse full of elves".to_string(),
-                false,
-                Key::new("".to_string()),
-                None,
-                "Elf Treehouse".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("beans".to_string(), "a can of beans".to_string(), 10, 0, 0),
-                Item::new("axe".to_string(), "a sharp axe".to_string(), 0, 10, 0),
-                Item::new("beanbag".to_string(), "a beanbag".to_string(), 20, 10, 0),
-            ],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Forest Clearing".to_string(),
-        Room::new(
-            "Forest Clearing".to_string(),
-            "a room with a lot of trees and a clearing".to_string(),
-            vec![Door::new(
-                "Forest Cabin".to_string(),
-                "a little hideaway".to_string(),
-                true,
-                Key::new("cabin weekend pass".to_string()),
-                None,
-                "Forest Cabin".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("stick".to_string(), "a stick".to_string(), 0, 5, 0),
-                Item::new("rock".to_string(), "a rock".to_string(), 5, 0, 0),
-                Item::new("mushroom".to_string(), "a mushroom".to_string(), 40, 0, 0),
-                Item::new("berry".to_string(), "a berry".to_string(), 10, 0, 0),
-            ],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Forest".to_string(),
-        Room::new(
-            "Forest".to_string(),
-            "a room with a lot of trees".to_string(),
-            vec![Door::new(
-                "Forest Clearing".to_string(),
-                "a room with a lot of trees and a clearing".to_string(),
-                false,
-                Key::new("".to_string()),
-                None,
-                "Forest Clearing".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("stick".to_string(), "a stick".to_string(), 0, 5, 0),
-                Item::new("rock".to_string(), "a rock".to_string(), 5, 0, 0),
-                Item::new("mushroom".to_string(), "a mushroom".to_string(), 40, 0, 0),
-                Item::new("berry".to_string(), "a berry".to_string(), 10, 0, 0),
-                Item::new(
-                    "felled tree".to_string(),
-                    "a felled tree".to_string(),
-                    0,
-                    50,
-                    0,
-                ),
-            ],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Jousting Store".to_string(),
-        Room::new(
-            "Jousting Store".to_string(),
-            "a room with a lot of jousting equipment".to_string(),
-            vec![],
-            vec![
-                Item::new("helmet".to_string(), "a helmet".to_string(), 20, 0, 0),
-                Item::new(
-                    "jousting stick".to_string(),
-                    "a jousting stick".to_string(),
-                    0,
-                    100,
-                    0,
-                ),
-            ],
-            vec![Key::new("cabin weekend pass".to_string())],
-        ),
-    );
-
-    rooms.insert(
-        "Jousting Arena".to_string(),
-        Room::new(
-            "Jousting Arena".to_string(),
-            "a room with a lot of horses and knights".to_string(),
-            vec![Door::new(
-                "Jousting Store".to_string(),
-                "a room with a lot of jousting equipment".to_string(),
-                false,
-                Key::new("".to_string()),
-                Some(Player::new(
-                    "Knight".to_string(),
-                    empty_map.clone(),
-                    vec![],
-                    vec![],
-                    400,
-                    80,
-                    vec![],
-                    empty_room.clone(),
-                    "".to_string(),
-                    40,
-                    Class::default(),
-                )),
-                "Armory".to_string(),
-                Some(RoomRequirements {
-                    health: 400,
-                    attack: 60,
-                    defense: 40,
-                }),
-            )],
-            vec![
-                Item::new("lance".to_string(), "a lance".to_string(), 0, 20, 0),
-                Item::new("horse".to_string(), "a horse".to_string(), 150, 0, 0),
-            ],
-            vec![],
-        ),
-    );
-
-    rooms.insert(
-        "Armory".to_string(),
-        Room::new(
-            "Armory".to_string(),
-            "a room with a lot of weapons".to_string(),
-            vec![Door::new(
-                "Trophy Cupboard".to_string(),
-                "a room with a lot of trophies".to_string(),
-                true,
-                Key::new("trophy cupboard".to_string()),
-                Some(Player::new(
-                    "Trophy Keeper".to_string(),
-                    empty_map.clone(),
-                    vec![],
-                    vec![],
-                    100,
-                    20,
-                    vec![],
-                    empty_room.clone(),
-                    "".to_string(),
-                    10,
-                    Class::default(),
-                )),
-                "Trophy Cupboard".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("shield".to_string(), "a shield".to_string(), 20, 0, 0),
-                Item::new("axe".to_string(), "a sharp axe".to_string(), 0, 20, 0),
-            ],
-            vec![
-                Key::new("trophy cupboard".to_string()),
-                Key::new("pantry".to_string()),
-            ],
-        ),
-    );
+    let classes = match flag_value(args, "-c", "--classes") {
+        Some(path) => load_classes_or_exit(path),
+        None => Class::builtins(),
+    };
 
-    rooms.insert(
-        "Trophy Cupboard".to_string(),
-        Room::new(
-            "Trophy Cupboard".to_string(),
-            "a room with a lot of trophies".to_string(),
-            vec![],
-            vec![
-                Item::new(
-                    "trophy".to_string(),
-                    "a golden trophy".to_string(),
-                    30,
-                    0,
-                    0,
-                ),
-                Item::new("bow".to_string(), "a bow".to_string(), 0, 30, 0),
-            ],
-            vec![Key::new("silverware drawer".to_string())],
-        ),
-    );
+    let riddles = match riddles_path(args, map_path, &map) {
+        Some(path) => load_riddles_or_exit(&path),
+        None => RiddlePack::builtin(),
+    };
 
-    rooms.insert(
-        "Pantry".to_string(),
-        Room::new(
-            "Pantry".to_string(),
-            "a room with a lot of food".to_string(),
-            vec![Door::new(
-                "Silverware Drawer".to_string(),
-                "a room with a lot of silverware".to_string(),
-                true,
-                Key::new("silverware drawer".to_string()),
-                Some(Player::new(
-                    "Silverware Demon".to_string(),
-                    empty_map.clone(),
-                    vec![],
-                    vec![],
-                    100,
-                    20,
-                    vec![],
-                    empty_room.clone(),
-                    "".to_string(),
-                    10,
-                    Class::default(),
-                )),
-                "Silverware Drawer".to_string(),
-                None,
-            )],
-            vec![
-                Item::new("bread".to_string(), "a loaf of bread".to_string(), 10, 0, 0),
-                Item::new("dagger".to_string(), "a sharp dagger".to_string(), 0, 10, 0),
-            ],
-            vec![],
-        ),
-    );
+    let issues = validate_map(&map, &classes);
 
-    rooms.insert(
-        "Silverware Drawer".to_string(),
-        Room::new(
-            "Silverware Drawer".to_string(),
-            "a room with a lot of silverware".to_string(),
-            vec![],
-            vec![
-                Item::new("fork".to_string(), "a fork".to_string(), 5, 0, 0),
-                Item::new("knife".to_string(), "a knife".to_string(), 0, 5, 0),
-                Item::new(
-                    "Grandma's Special Spoon".to_string(),
-                    "a spoon".to_string(),
-                    0,
-                    50,
-                    0,
-                ),
-            ],
-            vec![],
-        ),
-    );
+    for issue in &issues {
+        if issue.is_error() {
+            println!("error: {}", issue);
+        } else {
+            println!("warning: {}", issue);
+        }
+    }
 
-    rooms.insert(
-        "Dining Room".to_string(),
-        Room::new(
-            "Dining Room".to_string(),
-            "a room with a table and chairs".to_string(),
-            vec![],
-            vec![
-                Item::new("chair".to_string(), "a chair".to_string(), 0, 10, 0),
-                Item::new("tablecloth".to_string(), "a table".to_string(), 0, 20, 0),
-            ],
-            vec![],
-        ),
-    );
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
 
-    rooms.insert(
-        "Concert Hall".to_string(),
-        Room::new(
-            "Concert Hall".to_string(),
-            "a room with a stage and a lot of seats".to_string(),
-            vec![
-                Door::new(
-                    "Stage".to_string(),
-                    "a room with a stage".to_string(),
-                    true,
-                    Key::new("actor's pass".to_string()),
-                    None,
-                    "Stage".to_string(),
-                    Some(RoomRequirements {
-                        health: 300,
-                        attack: 0,
-                        defense: 0,
-                    }),
-                ),
-                Door::new(
-                    "Backstage".to_string(),
-                    "a room with a lot of props".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Backstage".to_string(),
-                    None,
-                ),
-            ],
-            vec![],
-            vec![],
-        ),
-    );
+    println!("{} error(s), {} warning(s)", errors, issues.len() - errors);
 
-    rooms.insert(
-        "Stage".to_string(),
-        Room::new(
-            "Stage".to_string(),
-            "a room with a stage".to_string(),
-            vec![],
-            vec![
-                Item::new(
-                    "microphone".to_string(),
-                    "a microphone".to_string(),
-                    0,
-                    10,
-                    0,
-                ),
-                Item::new("XLR cable".to_string(), "a mic cable".to_string(), 0, 20, 0),
-            ],
-            vec![],
-        ),
-    );
+    if errors > 0 {
+        std::process::exit(1);
+    }
 
-    rooms.insert(
-        "Backstage".to_string(),
-        Room::new(
-            "Backstage".to_string(),
-            "a room with a lot of props".to_string(),
-            vec![
-                Door::new(
-                    "Dressing Room".to_string(),
-                    "a room with a lot of costumes".to_string(),
-                    true,
-                    Key::new("staff pass".to_string()),
-                    Some(Player::new(
-                        "Stressed actor".to_string(),
-                        empty_map.clone(),
-                        vec![
-                            Item::new("costume".to_string(), "a costume".to_string(), 20, 0, 0),
-                            Item::new("makeup".to_string(), "a makeup kit".to_string(), 0, 5, 0),
-                        ],
-                        vec![],
-                        100,
-                        20,
-                        vec![],
-                        empty_room.clone(),
-                        "".to_string(),
-                        10,
-                        Class::default(),
-                    )),
-                    "Dressing Room".to_string(),
-                    None,
-                ),
-                Door::new(
-                    "Tech Room".to_string(),
-                    "a room with a lot of tech".to_string(),
-                    false,
-                    Key::new("".to_string()),
-                    None,
-                    "Tech Room".to_string(),
-                    None,
-                ),
-            ],
-            vec![
-                Item::new("guitar".to_string(), "a guitar".to_string(), 0, 30, 0),
-                Item::new(
-                    "drumsticks".to_string(),
-                    "a pair of drumsticks".to_string(),
-                    0,
-                    40,
-                    0,
-                ),
-            ],
-            vec![Key::new("staff pass".to_string())],
-        ),
-    );
+    for class in &classes {
+        let solution = solve(
+            &map.rooms,
+            &map.start_room,
+            class,
+            map.wrong_answer_policy(&riddles),
+            &map.rules,
+        );
 
-    rooms.insert(
-        "Dressing Room".to_string(),
-        Room::new(
-            "Dressing Room".to_string(),
-            "a room with a lot of costumes".to_string(),
-            vec![],
-            vec![
-                Item::new("costume".to_string(), "a costume".to_string(), 20, 0, 0),
-                Item::new("makeup".to_string(), "a makeup kit".to_string(), 0, 5, 0),
-            ],
-            vec![Key::new("actor's pass".to_string())],
-        ),
-    );
+        println!();
+        println!(
+            "{} can reach {} of {} rooms",
+            solution.class,
+            solution.reachable.len(),
+            map.rooms.len()
+        );
 
-    rooms.insert(
-        "Tech Room".to_string(),
-        Room::new(
-            "Tech Room".to_string(),
-            "a room with a lot of tech".to_string(),
-            vec![],
-            vec![
-                Item::new("laptop".to_string(), "a laptop".to_string(), 0, 30, 0),
-                Item::new(
-                    "headphones".to_string(),
-                    "a pair of headphones".to_string(),
-                    20,
-                    0,
-                    0,
-                ),
-                Item::new(
-                    "sound board".to_string(),
-                    "a sound board".to_string(),
-                    0,
-                    50,
-                    0,
-                ),
-            ],
-            vec![],
-        ),
-    );
+        if !solution.unreachable.is_empty() {
+            println!("  can't reach: {}", solution.unreachable.join(", "));
+        }
 
-    // if the -m or --map flag is passed, load a map json file
-    // example invocation: cargo run -- -m treehouse.map.json
+        for blocked in &solution.blocked {
+            println!("  {}", blocked);
+        }
 
-    if std::env::args()
-        .collect::<Vec<String>>()
-        .contains(&"-m".to_string())
-        || std::env::args()
-            .collect::<Vec<String>>()
-            .contains(&"--map".to_string())
-    {
-        let map_file_usize = std::env::args()
-            .collect::<Vec<String>>()
-            .iter()
-            .position(|arg| arg == "-m" || arg == "--map")
-            .unwrap();
+        for soft_lock in &solution.soft_locks {
+            println!("  {}", soft_lock);
+        }
+    }
+}
 
-        let map_file = std::env::args().collect::<Vec<String>>();
+/// Riddles come from -r or --riddles, or else the pack the map names, or else a .riddles.json next
+/// to the map.
+fn riddles_path(args: &[String], map_path: Option<&String>, map: &Map) -> Option<String> {
+    flag_value(args, "-r", "--riddles").cloned().or_else(|| {
+        let map_path = map_path?;
 
-        let map_file = map_file.get(map_file_usize + 1).unwrap();
+        map_riddles_path(map_path, map)
+            .or_else(|| sibling_riddles_path(map_path).filter(|path| Path::new(path).exists()))
+    })
+}
 
-        let map_file = map_file.as_str();
+fn load_riddles_or_exit(path: &str) -> RiddlePack {
+    load_riddles(path).unwrap_or_else(|e| {
+        eprintln!("Could not load riddles file: {}", e);
+        std::process::exit(1);
+    })
+}
 
-        let map_file = File::open(map_file).unwrap_or_else(|e| {
-            eprintln!("Could not open map file: {}", e);
+/// `gext saves [--save-dir dir]` lists saves, and `gext saves delete <name>` deletes one.
+fn saves(args: &[String], saves: &SaveManager) {
+    if args.get(2).is_some_and(|arg| arg == "delete") {
+        let Some(name) = args.get(3) else {
+            eprintln!("Expected the name of a savegame to delete");
             std::process::exit(1);
-        });
+        };
 
-        let map: HashMap<String, Room> = serde_json::from_reader(map_file).unwrap_or_else(|e| {
-            eprintln!("Could not parse map file: {}", e);
+        if let Err(e) = saves.delete(name) {
+            eprintln!("Could not delete savegame: {}", e);
             std::process::exit(1);
-        });
+        }
 
-        rooms = map;
+        println!("Deleted {}", saves.path(name).display());
+        return;
     }
 
-    let mut player: Player;
-
-    out!("Would you like to load a savegame? (y/n)", "yellow");
-
-    if term.read_line().unwrap().trim() == "y" {
-        out!("What is the name of the savegame?", "yellow");
-
-        let mut savegame_name = term.read_line().unwrap();
-
-        savegame_name = savegame_name.trim().to_string();
-
-        player = serde_json::from_reader(
-            File::open(format!("savegames/{}.json", savegame_name)).unwrap_or_else(|e| {
-                eprintln!("Could not open savegame file: {}", e);
-                std::process::exit(1);
-            }),
-        )
-        .unwrap();
-
-        write(
-            format!("You are in the {}", player.current_room.name).as_str(),
-            "blue",
-        );
-    } else {
-        player = Player::init(
-            "Player".to_string(),
-            rooms,
-            "".to_string(),
-            "Entrance Hall".to_string(),
-        );
-        out!("What is your name?", "yellow");
+    client::list_saves(&mut TermIo::new(), saves);
+}
 
-        player.name = term.read_line().unwrap().trim().to_string();
+fn main() {
+    let mut content = Content::default();
 
-        out!("What is the name of this game?", "yellow");
+    let args = std::env::args().collect::<Vec<String>>();
 
-        player.game_name = term.read_line().unwrap().trim().to_string();
+    let save_dir = flag_value(&args, "--save-dir", "--save-dir").map(PathBuf::from);
 
-        out!(format!(
-            "Welcome to the game, {}! You are in the {}",
-            player.name, player.current_room.name
-        )
-        .as_str());
+    match args.get(1).map(String::as_str) {
+        Some("validate") => {
+            validate(&args);
+            return;
+        }
+        Some("saves") => {
+            saves(
+                &args,
+                &save_dir.map_or_else(SaveManager::default, SaveManager::new),
+            );
+            return;
+        }
+        _ => {}
     }
 
-    loop {
-        let input = term.read_line().unwrap();
-
-        let input = input.trim();
+    // if the -m or --map flag is passed, load a map json file
+    // example invocation: cargo run -- -m treehouse.map.json
 
-        let mut commands: Vec<&str> = input.splitn(2, " ").collect();
+    let map_path = flag_value(&args, "-m", "--map");
 
-        if std::env::args()
-            .collect::<Vec<_>>()
-            .contains(&"-d".to_string())
-            || std::env::args()
-                .collect::<Vec<_>>()
-                .contains(&"--debug".to_string())
-        {
-            if commands[0] == "give" {
-                let item = Item::new(
-                    commands[1].to_string(),
-                    "a debug item".to_string(),
-                    100,
-                    100,
-                    100,
-                );
+    if let Some(map_file) = map_path {
+        content.map = load_map_or_exit(map_file);
+    }
 
-                player.items_held.push(item);
+    // example invocation: cargo run -- -c knights.classes.json
 
-                commands[0] = "blank";
-            } else if commands[0] == "givekey" {
-                let key = Key::new(commands[1].to_string());
+    if let Some(classes_file) = flag_value(&args, "-c", "--classes") {
+        content.classes = load_classes_or_exit(classes_file);
+    }
 
-                player.keys_held.push(key);
+    if let Some(riddles_file) = riddles_path(&args, map_path, &content.map) {
+        content.riddles = load_riddles_or_exit(&riddles_file);
+    }
 
-                commands[0] = "blank";
-            }
+    let seed = flag_value(&args, "--seed", "--seed").map(|seed| {
+        seed.parse::<u64>().unwrap_or_else(|_| {
+            eprintln!("Expected a number after --seed");
+            std::process::exit(1);
+        })
+    });
+
+    // a map with errors would crash part way through, so refuse to start it
+    let errors: Vec<Issue> = validate_map(&content.map, &content.classes)
+        .into_iter()
+        .filter(|issue| issue.is_error())
+        .collect();
+
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("Map error: {}", error);
         }
 
-        match commands[0] {
-            "blank" => {
-                continue;
-            }
-
-            "quit" => {
-                write("Goodbye!", "green");
-                player.save();
-                break;
-            }
-
-            "help" => {
-                out!(
-                    format!(
-                        "
-{}
-- look: print the description of the room
-- go [room]: move to another room (checks if you have the key)
-- take [item]: take an item
-- takekey [key]: take a key
-- search: search the room for items, keys, and doors
-- save: save the game
-- battles: print the battles you've fought
-- inventory: print the items and keys you have
-- use: use an item now, but for only half the effect",
-                        style("Commands:").bold()
-                    )
-                    .as_str(),
-                    "green"
-                );
-
-                if std::env::args()
-                    .collect::<Vec<String>>()
-                    .contains(&"--debug".to_string())
-                    || std::env::args()
-                        .collect::<Vec<String>>()
-                        .contains(&"-d".to_string())
-                {
-                    out!(
-                        format!(
-                            "{}
-- debug: print the current room's data",
-                            style("Debug Commands:").bold()
-                        )
-                        .as_str(),
-                        "green"
-                    );
-                }
-
-                out!(
-                    format!("If you can't figure out what to do, try using `search`!\nStuck for a key? Make sure to look around!",)
-                        .as_str(),
-                    "cyan"
-                )
-            }
-
-            "look" => {
-                out!(player.current_room.description.as_str());
-            }
-
-            "go" => {
-                if commands.len() < 2 {
-                    write("Go where?", "red");
-                } else {
-                    player.move_through_door(commands[1].to_string());
-                }
-            }
-
-            "take" => {
-                if commands.len() < 2 {
-                    write("Take what?", "red");
-                } else {
-                    player.take_item(commands[1].to_string());
-                }
-            }
-
-            "takekey" => {
-                if commands.len() < 2 {
-                    write("Take what?", "red");
-                } else {
-                    player.take_key(commands[1].to_string());
-                }
-            }
-
-            "use" => {
-                if commands.len() < 2 {
-                    write("Use what?", "red");
-                } else {
-                    let item_from_inventory =
-                        player.items_held.iter().find(|i| i.name == commands[1]);
-
-                    match item_from_inventory {
-                        Some(item) => {
-                            player.use_item(Item::new(
-                                item.name.clone(),
-                                item.description.clone(),
-                                item.health / 2 as i32,
-                                item.attack / 2 as i32,
-                                item.defense / 2 as i32,
-                            ));
-
-                            player.items_held.retain(|i| i.name != commands[1]);
-                        }
-                        None => {
-                            write("You don't have that item.", "red");
-                        }
-                    }
-                }
-            }
-
-            "debug" => {
-                println!("{:?}", player.current_room);
-            }
-
-            "search" => {
-                for item in &player.current_room.items {
-                    write(
-                        format!(
-                            "You see \"{}\" (item) that buffs {}❤️, {}🪓 and {}🛡️",
-                            item.name, item.health, item.attack, item.defense
-                        )
-                        .as_str(),
-                        "green",
-                    );
-                }
-
-                for key in &player.current_room.keys {
-                    write(format!("You see \"{}\" (key)", key.name).as_str(), "green");
-                }
-
-                for door in &player.current_room.doors {
-                    write(
-                        format!(
-                            "You see \"{}\" ({}, {} door)",
-                            door.name,
-                            if door.locked && !player.keys_held.contains(&door.key) {
-                                "locked"
-                            } else {
-                                "unlocked"
-                            },
-                            if door.enemy.is_some()
-                                && !player
-                                    .battles
-                                    .iter()
-                                    .any(|b| b.enemy_name == door.enemy.clone().unwrap().name)
-                            {
-                                "guarded"
-                            } else {
-                                "unguarded"
-                            }
-                        )
-                        .as_str(),
-                        "green",
-                    );
-                }
-            }
-
-            "save" => {
-                player.save();
-            }
-
-            "battles" => {
-                for battle in &player.battles {
-                    write(
-                        format!(
-                            "You fought \"{}\" and {}. You had {}❤️ and they had {}❤️.",
-                            battle.enemy_name,
-                            if battle.winner { "won" } else { "lost" },
-                            battle.player_health,
-                            battle.enemy_health
-                        )
-                        .as_str(),
-                        "green",
-                    );
-                }
-            }
-
-            "inventory" => {
-                for item in &player.items_held {
-                    write(
-                        format!(
-                            "You have the \"{}\" (item, \"{}\") that buffs {}❤️, {}🪓 and {}🛡️",
-                            item.name,
-                            truncate_string(item.description.as_str(), 50),
-                            item.health,
-                            item.attack,
-                            item.defense
-                        )
-                        .as_str(),
-                        "green",
-                    );
-                }
-
-                for key in &player.keys_held {
-                    write(format!("You have the {} (key)", key.name).as_str(), "green");
-                }
-
-                if player.keys_held.len() == 0 && player.items_held.len() == 0 {
-                    out!("You have nothing in your inventory.", "red");
-                }
-            }
+        eprintln!("Run `gext validate` on the map for more details");
+        std::process::exit(1);
+    }
 
-            "stats" => {
-                write(
-                    format!(
-                        "You have {}❤️, {}🪓 and {}🛡️. Your class is {}.",
-                        player.health, player.attack, player.defense, player.class.name
-                    )
-                    .as_str(),
-                    "green",
-                );
-            }
+    let backups = flag_value(&args, "--backups", "--backups").map_or(DEFAULT_BACKUPS, |backups| {
+        backups.parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("Expected a number after --backups");
+            std::process::exit(1);
+        })
+    });
+
+    let options = Options {
+        debug: has_flag("-d", "--debug"),
+        no_save: has_flag("-n", "--no-save"),
+        seed,
+        permadeath: has_flag("--permadeath", "--permadeath"),
+        save_dir,
+        backups,
+    };
 
-            _ => {
-                write(
-                    "I don't understand that command. Try using `help` if you need it!",
-                    "red",
-                );
-            }
-        }
-    }
+    client::play(&mut TermIo::new(), content, &options);
 }
@@ -0,0 +1,903 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::damage::Rules;
+use crate::riddles::{RiddlePack, WrongAnswerPolicy};
+use crate::structs::{Door, Enemy, Item, Key, Player, Room, RoomRequirements};
+
+/// Where new games start, unless the map says otherwise.
+pub const START_ROOM: &str = "Entrance Hall";
+
+/// What the player has to do to finish a map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WinCondition {
+    ReachRoom {
+        room: String,
+    },
+    /// Holding every one of these items at once, by name.
+    HoldItems {
+        items: Vec<String>,
+    },
+    DefeatEnemy {
+        enemy: String,
+    },
+    /// Winning this many battles.
+    SurviveBattles {
+        count: usize,
+    },
+}
+
+/// What ends a map in defeat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoseCondition {
+    /// Losing this many battles.
+    LoseBattles { count: usize },
+    /// Running out of turns, where a turn is a move that changes the world; see [`Player::turn`].
+    TurnLimit { turns: u64 },
+}
+
+/// A map file: a header describing the map, and its rooms.
+///
+/// Older map files are just the rooms; [`Map::from_rooms`] gives them an empty header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Map {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default = "default_start_room")]
+    pub start_room: String,
+    /// Shown when a new game starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intro: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_condition: Option<WinCondition>,
+    /// Defaults to losing a single battle. `null` means the game can't be lost.
+    #[serde(default = "default_lose_condition")]
+    pub lose_condition: Option<LoseCondition>,
+    #[serde(default, skip_serializing_if = "Rules::is_default")]
+    pub rules: Rules,
+    /// The riddle pack the map uses, relative to the map file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub riddles: Option<String>,
+    /// What wrong answers cost on this map, in place of the riddle pack's `on_wrong_answer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrong_answer: Option<WrongAnswerPolicy>,
+
+    pub rooms: HashMap<String, Room>,
+}
+
+fn default_start_room() -> String {
+    START_ROOM.to_string()
+}
+
+fn default_lose_condition() -> Option<LoseCondition> {
+    Some(LoseCondition::LoseBattles { count: 1 })
+}
+
+impl WinCondition {
+    pub fn is_met(&self, player: &Player) -> bool {
+        match self {
+            WinCondition::ReachRoom { room } => &player.current_room == room,
+            WinCondition::HoldItems { items } => items
+                .iter()
+                .all(|name| player.items_held.iter().any(|i| &i.name == name)),
+            WinCondition::DefeatEnemy { enemy } => player
+                .battles
+                .iter()
+                .any(|b| b.winner && !b.fled && &b.enemy_name == enemy),
+            WinCondition::SurviveBattles { count } => {
+                player.battles.iter().filter(|b| b.winner).count() >= *count
+            }
+        }
+    }
+}
+
+impl LoseCondition {
+    pub fn is_met(&self, player: &Player) -> bool {
+        match self {
+            LoseCondition::LoseBattles { count } => {
+                player
+                    .battles
+                    .iter()
+                    .skip(player.battles_before_endings)
+                    .filter(|b| !b.winner)
+                    .count()
+                    >= *count
+            }
+            LoseCondition::TurnLimit { turns } => player.turn >= *turns,
+        }
+    }
+}
+
+impl fmt::Display for WinCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinCondition::ReachRoom { room } => write!(f, "reach the {}", room),
+            WinCondition::HoldItems { items } => write!(f, "hold the {}", items.join(", ")),
+            WinCondition::DefeatEnemy { enemy } => write!(f, "defeat {}", enemy),
+            WinCondition::SurviveBattles { count } => write!(f, "win {} battles", count),
+        }
+    }
+}
+
+impl fmt::Display for LoseCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoseCondition::LoseBattles { count: 1 } => write!(f, "lose a battle"),
+            LoseCondition::LoseBattles { count } => write!(f, "lose {} battles", count),
+            LoseCondition::TurnLimit { turns } => write!(f, "use up all {} turns", turns),
+        }
+    }
+}
+
+impl Map {
+    pub fn from_rooms(mut rooms: HashMap<String, Room>) -> Map {
+        link_doors(&mut rooms);
+
+        Map {
+            title: String::new(),
+            author: None,
+            version: None,
+            start_room: default_start_room(),
+            intro: None,
+            win_condition: None,
+            lose_condition: default_lose_condition(),
+            rules: Rules::default(),
+            riddles: None,
+            wrong_answer: None,
+            rooms,
+        }
+    }
+
+    /// What wrong answers cost: the map's own `wrong_answer` policy, or else the riddle pack's.
+    pub fn wrong_answer_policy<'a>(&'a self, riddles: &'a RiddlePack) -> &'a WrongAnswerPolicy {
+        self.wrong_answer
+            .as_ref()
+            .unwrap_or(&riddles.on_wrong_answer)
+    }
+
+    /// The built-in map, used when no `--map` file is given.
+    pub fn builtin() -> Map {
+        Map::from_rooms(default_map())
+    }
+}
+
+/// Adds the way back through every two-way door to the room it leads to, unless that room already
+/// has a door leading back or one with the same name. Linking rooms again changes nothing.
+pub fn link_doors(rooms: &mut HashMap<String, Room>) {
+    let mut names: Vec<String> = rooms.keys().cloned().collect();
+    names.sort();
+
+    for name in names {
+        for door in rooms[&name].doors.clone() {
+            if door.one_way {
+                continue;
+            }
+
+            let side = door.back.unwrap_or_default();
+            let back_name = side.name.unwrap_or_else(|| name.clone());
+
+            let Some(target) = rooms.get_mut(&door.associated_room_name) else {
+                continue;
+            };
+
+            if target
+                .doors
+                .iter()
+                .any(|d| d.associated_room_name == name || d.name == back_name)
+            {
+                continue;
+            }
+
+            target.doors.push(Door {
+                name: back_name,
+                description: side
+                    .description
+                    .unwrap_or_else(|| format!("a door to the {}", name)),
+                locked: side.locked,
+                key: side.key,
+                enemy: side.enemy,
+                associated_room_name: name.clone(),
+                requirements: side.requirements,
+                one_way: false,
+                back: None,
+            });
+        }
+    }
+}
+
+/// The rooms of the built-in map.
+pub fn default_map() -> HashMap<String, Room> {
+    let mut rooms = HashMap::new();
+
+    rooms.insert(
+        "Kitchen".to_string(),
+        Room::new(
+            "Kitchen".to_string(),
+            "a room with a stove and a fridge".to_string(),
+            vec![
+                Door::new(
+                    "Pantry".to_string(),
+                    "a room with a lot of food".to_string(),
+                    true,
+                    Key::new("pantry".to_string()),
+                    None,
+                    "Pantry".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Dining Room".to_string(),
+                    "a room with a table and chairs".to_string(),
+                    false,
+                    Key::new("dining room".to_string()),
+                    None,
+                    "Dining Room".to_string(),
+                    None,
+                ),
+            ],
+            vec![
+                Item::new("apple".to_string(), "a red apple".to_string(), 10, 0, 0),
+                Item::new("sword".to_string(), "a sharp sword".to_string(), 0, 10, 0),
+            ],
+            vec![Key::new("elf basement".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Entrance Hall".to_string(),
+        Room::new(
+            "Entrance Hall".to_string(),
+            "the first room - the entrance hall".to_string(),
+            vec![
+                Door::new(
+                    "Kitchen".to_string(),
+                    "a room with a stove and a fridge".to_string(),
+                    true,
+                    Key::new("kitchen".to_string()),
+                    None,
+                    "Kitchen".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Armory".to_string(),
+                    "a room with a lot of weapons".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "Armory".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Concert Hall".to_string(),
+                    "a room with a stage and a lot of seats".to_string(),
+                    true,
+                    Key::new("concert hall ticket".to_string()),
+                    None,
+                    "Concert Hall".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Forest".to_string(),
+                    "a room with a lot of trees".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "Forest".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Downstairs Staircase".to_string(),
+                    "a staircase leading to the basement".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "Downstairs Staircase".to_string(),
+                    None,
+                ),
+            ],
+            vec![
+                Item::new("potion".to_string(), "a red potion".to_string(), 20, 0, 0),
+                Item::new(
+                    "poison vial".to_string(),
+                    "a poisonous liquid that can be used to throw at your enemy".to_string(),
+                    0,
+                    20,
+                    0,
+                ),
+            ],
+            vec![
+                Key::new("kitchen".to_string()),
+                Key::new("concert hall ticket".to_string()),
+            ],
+        ),
+    );
+
+    rooms.insert(
+        "North Dungeon".to_string(),
+        Room::new(
+            "North Dungeon".to_string(),
+            "a dark dungeon".to_string(),
+            vec![],
+            vec![Item::new(
+                "boulder".to_string(),
+                "a boulder".to_string(),
+                50,
+                0,
+                0,
+            )],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "East Dungeon".to_string(),
+        Room::new(
+            "East Dungeon".to_string(),
+            "a dark dungeon".to_string(),
+            vec![Door::new(
+                "East Dungeon Cell".to_string(),
+                "a dark dungeon cell".to_string(),
+                false,
+                Key::new("".to_string()),
+                None,
+                "East Dungeon Cell".to_string(),
+                None,
+            )],
+            vec![Item::new(
+                "stick".to_string(),
+                "a stick".to_string(),
+                0,
+                1,
+                0,
+            )],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "West Dungeon".to_string(),
+        Room::new(
+            "West Dungeon".to_string(),
+            "a dark dungeon".to_string(),
+            vec![],
+            vec![Item::new(
+                "stale bread".to_string(),
+                "a loaf of bread".to_string(),
+                -10,
+                0,
+                0,
+            )],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "South Dungeon".to_string(),
+        Room::new(
+            "South Dungeon".to_string(),
+            "a dark dungeon".to_string(),
+            vec![],
+            vec![Item::new(
+                "window bar".to_string(),
+                "a bar".to_string(),
+                0,
+                50,
+                0,
+            )],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "East Dungeon Cell".to_string(),
+        Room::new(
+            "East Dungeon Cell".to_string(),
+            "a dark dungeon cell".to_string(),
+            vec![],
+            vec![Item::new(
+                "chain".to_string(),
+                "a chain".to_string(),
+                0,
+                100,
+                0,
+            )],
+            vec![Key::new("dungeon key".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Dungeon Corridor".to_string(),
+        Room::new(
+            "Dungeon Corridor".to_string(),
+            "a dark corridor".to_string(),
+            vec![
+                Door::new(
+                    "North Dungeon".to_string(),
+                    "a dark dungeon".to_string(),
+                    true,
+                    Key::new("dungeon key".to_string()),
+                    None,
+                    "North Dungeon".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "East Dungeon".to_string(),
+                    "a dark dungeon".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "East Dungeon".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "West Dungeon".to_string(),
+                    "a dark dungeon".to_string(),
+                    true,
+                    Key::new("dungeon key".to_string()),
+                    None,
+                    "West Dungeon".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "South Dungeon".to_string(),
+                    "a dark dungeon".to_string(),
+                    false,
+                    Key::new("dungeon key".to_string()),
+                    None,
+                    "South Dungeon".to_string(),
+                    None,
+                ),
+            ],
+            vec![],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Downstairs Staircase".to_string(),
+        Room::new(
+            "Downstairs Staircase".to_string(),
+            "a staircase leading to the basement".to_string(),
+            vec![Door::new(
+                "Dungeon Corridor".to_string(),
+                "a dark corridor".to_string(),
+                true,
+                Key::new("dungeons".to_string()),
+                None,
+                "Dungeon Corridor".to_string(),
+                None,
+            )],
+            vec![],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Elf Treehouse".to_string(),
+        Room::new(
+            "Elf Treehouse".to_string(),
+            "a treehouse full of elves".to_string(),
+            vec![Door::new(
+                "Elf Basement".to_string(),
+                "a basement full of elves".to_string(),
+                true,
+                Key::new("elf basement".to_string()),
+                Some(Enemy::new("Elf".to_string(), vec![], 800, 200, 100)),
+                "Elf Basement".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("elf hat".to_string(), "a hat".to_string(), 0, 10, 0),
+                Item::new(
+                    "elf shoes".to_string(),
+                    "a pair of shoes".to_string(),
+                    10,
+                    0,
+                    0,
+                ),
+            ],
+            vec![Key::new("dungeons".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Elf Basement".to_string(),
+        Room::new(
+            "Elf Basement".to_string(),
+            "a basement full of elves".to_string(),
+            vec![],
+            vec![Item::new(
+                "elven scythe".to_string(),
+                "a scythe".to_string(),
+                0,
+                70,
+                0,
+            )],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Forest Cabin".to_string(),
+        Room::new(
+            "Forest Cabin".to_string(),
+            "a little hideaway".to_string(),
+            vec![Door::new(
+                "Elf Treehouse".to_string(),
+                "a treehouse full of elves".to_string(),
+                false,
+                Key::new("".to_string()),
+                None,
+                "Elf Treehouse".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("beans".to_string(), "a can of beans".to_string(), 10, 0, 0),
+                Item::new("axe".to_string(), "a sharp axe".to_string(), 0, 10, 0),
+                Item::new("beanbag".to_string(), "a beanbag".to_string(), 20, 10, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Forest Clearing".to_string(),
+        Room::new(
+            "Forest Clearing".to_string(),
+            "a room with a lot of trees and a clearing".to_string(),
+            vec![Door::new(
+                "Forest Cabin".to_string(),
+                "a little hideaway".to_string(),
+                true,
+                Key::new("cabin weekend pass".to_string()),
+                None,
+                "Forest Cabin".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("stick".to_string(), "a stick".to_string(), 0, 5, 0),
+                Item::new("rock".to_string(), "a rock".to_string(), 5, 0, 0),
+                Item::new("mushroom".to_string(), "a mushroom".to_string(), 40, 0, 0),
+                Item::new("berry".to_string(), "a berry".to_string(), 10, 0, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Forest".to_string(),
+        Room::new(
+            "Forest".to_string(),
+            "a room with a lot of trees".to_string(),
+            vec![Door::new(
+                "Forest Clearing".to_string(),
+                "a room with a lot of trees and a clearing".to_string(),
+                false,
+                Key::new("".to_string()),
+                None,
+                "Forest Clearing".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("stick".to_string(), "a stick".to_string(), 0, 5, 0),
+                Item::new("rock".to_string(), "a rock".to_string(), 5, 0, 0),
+                Item::new("mushroom".to_string(), "a mushroom".to_string(), 40, 0, 0),
+                Item::new("berry".to_string(), "a berry".to_string(), 10, 0, 0),
+                Item::new(
+                    "felled tree".to_string(),
+                    "a felled tree".to_string(),
+                    0,
+                    50,
+                    0,
+                ),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Jousting Store".to_string(),
+        Room::new(
+            "Jousting Store".to_string(),
+            "a room with a lot of jousting equipment".to_string(),
+            vec![],
+            vec![
+                Item::new("helmet".to_string(), "a helmet".to_string(), 20, 0, 0),
+                Item::new(
+                    "jousting stick".to_string(),
+                    "a jousting stick".to_string(),
+                    0,
+                    100,
+                    0,
+                ),
+            ],
+            vec![Key::new("cabin weekend pass".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Jousting Arena".to_string(),
+        Room::new(
+            "Jousting Arena".to_string(),
+            "a room with a lot of horses and knights".to_string(),
+            vec![Door::new(
+                "Jousting Store".to_string(),
+                "a room with a lot of jousting equipment".to_string(),
+                false,
+                Key::new("".to_string()),
+                Some(Enemy::new("Knight".to_string(), vec![], 400, 80, 40)),
+                "Armory".to_string(),
+                Some(RoomRequirements {
+                    health: 400,
+                    attack: 60,
+                    defense: 40,
+                }),
+            )],
+            vec![
+                Item::new("lance".to_string(), "a lance".to_string(), 0, 20, 0),
+                Item::new("horse".to_string(), "a horse".to_string(), 150, 0, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Armory".to_string(),
+        Room::new(
+            "Armory".to_string(),
+            "a room with a lot of weapons".to_string(),
+            vec![Door::new(
+                "Trophy Cupboard".to_string(),
+                "a room with a lot of trophies".to_string(),
+                true,
+                Key::new("trophy cupboard".to_string()),
+                Some(Enemy::new("Trophy Keeper".to_string(), vec![], 100, 20, 10)),
+                "Trophy Cupboard".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("shield".to_string(), "a shield".to_string(), 20, 0, 0),
+                Item::new("axe".to_string(), "a sharp axe".to_string(), 0, 20, 0),
+            ],
+            vec![
+                Key::new("trophy cupboard".to_string()),
+                Key::new("pantry".to_string()),
+            ],
+        ),
+    );
+
+    rooms.insert(
+        "Trophy Cupboard".to_string(),
+        Room::new(
+            "Trophy Cupboard".to_string(),
+            "a room with a lot of trophies".to_string(),
+            vec![],
+            vec![
+                Item::new(
+                    "trophy".to_string(),
+                    "a golden trophy".to_string(),
+                    30,
+                    0,
+                    0,
+                ),
+                Item::new("bow".to_string(), "a bow".to_string(), 0, 30, 0),
+            ],
+            vec![Key::new("silverware drawer".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Pantry".to_string(),
+        Room::new(
+            "Pantry".to_string(),
+            "a room with a lot of food".to_string(),
+            vec![Door::new(
+                "Silverware Drawer".to_string(),
+                "a room with a lot of silverware".to_string(),
+                true,
+                Key::new("silverware drawer".to_string()),
+                Some(Enemy::new(
+                    "Silverware Demon".to_string(),
+                    vec![],
+                    100,
+                    20,
+                    10,
+                )),
+                "Silverware Drawer".to_string(),
+                None,
+            )],
+            vec![
+                Item::new("bread".to_string(), "a loaf of bread".to_string(), 10, 0, 0),
+                Item::new("dagger".to_string(), "a sharp dagger".to_string(), 0, 10, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Silverware Drawer".to_string(),
+        Room::new(
+            "Silverware Drawer".to_string(),
+            "a room with a lot of silverware".to_string(),
+            vec![],
+            vec![
+                Item::new("fork".to_string(), "a fork".to_string(), 5, 0, 0),
+                Item::new("knife".to_string(), "a knife".to_string(), 0, 5, 0),
+                Item::new(
+                    "Grandma's Special Spoon".to_string(),
+                    "a spoon".to_string(),
+                    0,
+                    50,
+                    0,
+                ),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Dining Room".to_string(),
+        Room::new(
+            "Dining Room".to_string(),
+            "a room with a table and chairs".to_string(),
+            vec![],
+            vec![
+                Item::new("chair".to_string(), "a chair".to_string(), 0, 10, 0),
+                Item::new("tablecloth".to_string(), "a table".to_string(), 0, 20, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Concert Hall".to_string(),
+        Room::new(
+            "Concert Hall".to_string(),
+            "a room with a stage and a lot of seats".to_string(),
+            vec![
+                Door::new(
+                    "Stage".to_string(),
+                    "a room with a stage".to_string(),
+                    true,
+                    Key::new("actor's pass".to_string()),
+                    None,
+                    "Stage".to_string(),
+                    Some(RoomRequirements {
+                        health: 300,
+                        attack: 0,
+                        defense: 0,
+                    }),
+                ),
+                Door::new(
+                    "Backstage".to_string(),
+                    "a room with a lot of props".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "Backstage".to_string(),
+                    None,
+                ),
+            ],
+            vec![],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Stage".to_string(),
+        Room::new(
+            "Stage".to_string(),
+            "a room with a stage".to_string(),
+            vec![],
+            vec![
+                Item::new(
+                    "microphone".to_string(),
+                    "a microphone".to_string(),
+                    0,
+                    10,
+                    0,
+                ),
+                Item::new("XLR cable".to_string(), "a mic cable".to_string(), 0, 20, 0),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms.insert(
+        "Backstage".to_string(),
+        Room::new(
+            "Backstage".to_string(),
+            "a room with a lot of props".to_string(),
+            vec![
+                Door::new(
+                    "Dressing Room".to_string(),
+                    "a room with a lot of costumes".to_string(),
+                    true,
+                    Key::new("staff pass".to_string()),
+                    Some(Enemy::new(
+                        "Stressed actor".to_string(),
+                        vec![
+                            Item::new("costume".to_string(), "a costume".to_string(), 20, 0, 0),
+                            Item::new("makeup".to_string(), "a makeup kit".to_string(), 0, 5, 0),
+                        ],
+                        100,
+                        20,
+                        10,
+                    )),
+                    "Dressing Room".to_string(),
+                    None,
+                ),
+                Door::new(
+                    "Tech Room".to_string(),
+                    "a room with a lot of tech".to_string(),
+                    false,
+                    Key::new("".to_string()),
+                    None,
+                    "Tech Room".to_string(),
+                    None,
+                ),
+            ],
+            vec![
+                Item::new("guitar".to_string(), "a guitar".to_string(), 0, 30, 0),
+                Item::new(
+                    "drumsticks".to_string(),
+                    "a pair of drumsticks".to_string(),
+                    0,
+                    40,
+                    0,
+                ),
+            ],
+            vec![Key::new("staff pass".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Dressing Room".to_string(),
+        Room::new(
+            "Dressing Room".to_string(),
+            "a room with a lot of costumes".to_string(),
+            vec![],
+            vec![
+                Item::new("costume".to_string(), "a costume".to_string(), 20, 0, 0),
+                Item::new("makeup".to_string(), "a makeup kit".to_string(), 0, 5, 0),
+            ],
+            vec![Key::new("actor's pass".to_string())],
+        ),
+    );
+
+    rooms.insert(
+        "Tech Room".to_string(),
+        Room::new(
+            "Tech Room".to_string(),
+            "a room with a lot of tech".to_string(),
+            vec![],
+            vec![
+                Item::new("laptop".to_string(), "a laptop".to_string(), 0, 30, 0),
+                Item::new(
+                    "headphones".to_string(),
+                    "a pair of headphones".to_string(),
+                    20,
+                    0,
+                    0,
+                ),
+                Item::new(
+                    "sound board".to_string(),
+                    "a sound board".to_string(),
+                    0,
+                    50,
+                    0,
+                ),
+            ],
+            vec![],
+        ),
+    );
+
+    rooms
+}
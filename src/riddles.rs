@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::answers::AnswerMatching;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Riddle {
+    pub id: String,
+    pub question: String,
+    /// Any of these is accepted.
+    pub answers: Vec<String>,
+
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// What happens when a riddle is answered wrongly.
+///
+/// The default is the classic rule: one wrong answer and the item or key is gone for good.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WrongAnswerPolicy {
+    /// Wrong answers allowed before the item or key despawns.
+    pub attempts: u32,
+    /// Health lost for each wrong answer. This never takes the player below 1 health.
+    pub health_cost: i32,
+    /// Turns until a despawned item or key comes back, or `None` for never.
+    pub respawn_after: Option<u64>,
+    /// Whether keys can despawn at all. Turning this off means a key can never be lost.
+    pub despawn_keys: bool,
+}
+
+impl Default for WrongAnswerPolicy {
+    fn default() -> WrongAnswerPolicy {
+        WrongAnswerPolicy {
+            attempts: 1,
+            health_cost: 0,
+            respawn_after: None,
+            despawn_keys: true,
+        }
+    }
+}
+
+/// Which riddle guards an item or key: a riddle id, or any riddle matching a filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RiddleChoice {
+    Id(String),
+    Filter {
+        #[serde(default)]
+        category: Option<String>,
+        #[serde(default)]
+        difficulty: Option<Difficulty>,
+    },
+}
+
+/// A set of riddles, plus which of them guard which items and keys.
+///
+/// Items and keys are looked up by name; anything not listed gets a random riddle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiddlePack {
+    pub riddles: Vec<Riddle>,
+
+    #[serde(default)]
+    pub matching: AnswerMatching,
+    #[serde(default)]
+    pub on_wrong_answer: WrongAnswerPolicy,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub items: HashMap<String, RiddleChoice>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keys: HashMap<String, RiddleChoice>,
+}
+
+impl RiddleChoice {
+    pub fn matches(&self, riddle: &Riddle) -> bool {
+        match self {
+            RiddleChoice::Id(id) => &riddle.id == id,
+            RiddleChoice::Filter {
+                category,
+                difficulty,
+            } => {
+                category
+                    .as_ref()
+                    .is_none_or(|c| riddle.category.as_ref() == Some(c))
+                    && difficulty.is_none_or(|d| riddle.difficulty == d)
+            }
+        }
+    }
+}
+
+impl RiddlePack {
+    pub fn is_correct(&self, riddle: &Riddle, input: &str) -> bool {
+        self.matching
+            .accepts(&riddle.question, &riddle.answers, input)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Riddle> {
+        self.riddles.iter().find(|r| r.id == id)
+    }
+
+    pub fn choose_for_item(&self, name: &str, rng: &mut impl Rng) -> &Riddle {
+        self.choose(self.items.get(name), rng)
+    }
+
+    pub fn choose_for_key(&self, name: &str, rng: &mut impl Rng) -> &Riddle {
+        self.choose(self.keys.get(name), rng)
+    }
+
+    fn choose(&self, choice: Option<&RiddleChoice>, rng: &mut impl Rng) -> &Riddle {
+        let mut candidates: Vec<&Riddle> = match choice {
+            Some(choice) => self.riddles.iter().filter(|r| choice.matches(r)).collect(),
+            None => vec![],
+        };
+
+        if candidates.is_empty() {
+            candidates = self.riddles.iter().collect();
+        }
+
+        candidates[rng.gen_range(0..candidates.len())]
+    }
+
+    /// The riddles the game has always shipped with.
+    pub fn builtin() -> RiddlePack {
+        let riddle = |id: &str,
+                      question: &str,
+                      answers: &[&str],
+                      difficulty: Difficulty,
+                      category: &str,
+                      hint: &str| Riddle {
+            id: id.to_string(),
+            question: question.to_string(),
+            answers: answers.iter().map(|a| a.to_string()).collect(),
+            difficulty,
+            category: Some(category.to_string()),
+            hint: Some(hint.to_string()),
+        };
+
+        RiddlePack {
+            riddles: vec![
+                riddle(
+                    "ducks",
+                    "There are two ducks in front of a duck, two ducks behind a duck and a duck in the middle. How many ducks are there?",
+                    &["3", "three"],
+                    Difficulty::Medium,
+                    "logic",
+                    "Fewer than you think.",
+                ),
+                riddle(
+                    "piano",
+                    "What has keys but can't open locks?",
+                    &["piano"],
+                    Difficulty::Easy,
+                    "objects",
+                    "It makes music.",
+                ),
+                riddle(
+                    "penny",
+                    "What has a head, a tail, is brown, and has no legs?",
+                    &["penny"],
+                    Difficulty::Medium,
+                    "objects",
+                    "You might find one down the back of the sofa.",
+                ),
+                riddle(
+                    "bottle",
+                    "What has a neck but no head?",
+                    &["bottle"],
+                    Difficulty::Easy,
+                    "objects",
+                    "You drink from it.",
+                ),
+                riddle(
+                    "glove",
+                    "What has a thumb and four fingers but is not alive?",
+                    &["glove"],
+                    Difficulty::Easy,
+                    "objects",
+                    "You wear it in winter.",
+                ),
+                riddle(
+                    "artichoke",
+                    "What has a heart that doesn't beat?",
+                    &["artichoke"],
+                    Difficulty::Hard,
+                    "nature",
+                    "It's a vegetable.",
+                ),
+                riddle(
+                    "snail",
+                    "What has a foot but no legs?",
+                    &["snail"],
+                    Difficulty::Hard,
+                    "nature",
+                    "It's slow, and carries its house around.",
+                ),
+                riddle(
+                    "tree",
+                    "What has a bark but no bite?",
+                    &["tree"],
+                    Difficulty::Easy,
+                    "nature",
+                    "It grows in a forest.",
+                ),
+                riddle(
+                    "river",
+                    "What has a bed but never sleeps?",
+                    &["river"],
+                    Difficulty::Medium,
+                    "nature",
+                    "It flows to the sea.",
+                ),
+                riddle(
+                    "clock",
+                    "What has a face and two hands but no arms or legs?",
+                    &["clock"],
+                    Difficulty::Easy,
+                    "objects",
+                    "It tells you something.",
+                ),
+                riddle(
+                    "coin",
+                    "What has a head and a tail but no body?",
+                    &["coin"],
+                    Difficulty::Medium,
+                    "objects",
+                    "Flip it.",
+                ),
+                riddle(
+                    "shoe",
+                    "What has a tongue but cannot talk?",
+                    &["shoe"],
+                    Difficulty::Medium,
+                    "objects",
+                    "It comes in pairs.",
+                ),
+                riddle(
+                    "telephone",
+                    "What has a ring but no finger?",
+                    &["telephone"],
+                    Difficulty::Medium,
+                    "objects",
+                    "Hello?",
+                ),
+                riddle(
+                    "mississippi",
+                    "Mississippi has two 'p's and four 's's. Now, without using 'p' or 's', spell it.",
+                    &["it"],
+                    Difficulty::Hard,
+                    "wordplay",
+                    "Read the question again, very literally.",
+                ),
+                riddle(
+                    "car door",
+                    "A man in a car saw a golden door, a silver door, and a bronze door. What door did he open first?",
+                    &["car door", "car"],
+                    Difficulty::Hard,
+                    "wordplay",
+                    "Where is he sitting?",
+                ),
+            ],
+            matching: AnswerMatching::default(),
+            on_wrong_answer: WrongAnswerPolicy::default(),
+            items: HashMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The random number generator behind every battle roll and riddle pick.
+///
+/// It is seeded from a single `u64`, and serializes as that seed plus how far
+/// through its stream it has got, so a loaded game carries on exactly where
+/// the saved one stopped.
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    inner: ChaCha8Rng,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GameRngState {
+    seed: u64,
+    word_pos: u128,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        GameRng {
+            seed,
+            inner: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_entropy() -> GameRng {
+        GameRng::new(rand::random())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.inner.try_fill_bytes(dest)
+    }
+}
+
+impl Serialize for GameRng {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameRngState {
+            seed: self.seed,
+            word_pos: self.inner.get_word_pos(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameRng {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GameRng, D::Error> {
+        let state = GameRngState::deserialize(deserializer)?;
+
+        let mut rng = GameRng::new(state.seed);
+        rng.inner.set_word_pos(state.word_pos);
+
+        Ok(rng)
+    }
+}
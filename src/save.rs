@@ -0,0 +1,703 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::map::{link_doors, Map};
+use crate::rng::GameRng;
+use crate::structs::{
+    BattleResult, Class, Despawned, Ending, Enemy, Item, Key, Pickup, Player, Room,
+};
+use crate::validate::{validate_map, Issue};
+
+/// How many autosaves each game keeps, unless told otherwise.
+pub const DEFAULT_BACKUPS: usize = 3;
+
+/// Bumped whenever the save format changes, along with a new migration to upgrade older saves.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Upgrades a save by one format version: the first entry takes version 0 to 1, and so on.
+type Migration = fn(Value) -> Value;
+
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [wrap_bare_player, room_by_name];
+
+/// What goes in a `.save.json` file: the player, plus what's needed to load it safely later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub format_version: u32,
+    /// The version of gext that wrote the save.
+    pub game_version: String,
+    /// The [`fingerprint`] of the map the game was started on, if known.
+    pub map_fingerprint: Option<String>,
+    pub player: SavedPlayer,
+}
+
+/// A [`Player`] as it's saved: the map isn't stored, just how the world differs from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub class: Class,
+    pub name: String,
+    pub items_held: Vec<Item>,
+    pub keys_held: Vec<Key>,
+    pub health: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub battles: Vec<BattleResult>,
+    #[serde(default, skip_serializing_if = "crate::structs::is_zero")]
+    pub battles_before_endings: usize,
+    /// The name of the room the player is in.
+    pub current_room: String,
+    pub game_name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng: Option<GameRng>,
+    #[serde(default)]
+    pub turn: u64,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub riddle_failures: HashMap<String, u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub despawned: Vec<Despawned>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ending: Option<Ending>,
+    #[serde(default)]
+    pub permadeath: bool,
+
+    #[serde(default)]
+    pub world: WorldChanges,
+    /// The whole map, for saves made before saves stored changes, or without the map to hand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<HashMap<String, Room>>,
+}
+
+/// How the world differs from the map it started as.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldChanges {
+    /// Items and keys taken or lost. Only their names matter, so fixing their stats in the map
+    /// doesn't break saves.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<Placed>,
+    /// Items and keys that are in a room but weren't in the map to begin with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<Placed>,
+    /// Doors locked in the map that have been unlocked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocked: Vec<DoorRef>,
+    /// Doors that have been locked, which weren't in the map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locked: Vec<DoorRef>,
+    /// Doors whose enemy has dropped its loot already.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub looted: Vec<DoorRef>,
+}
+
+/// A door, by the room it's in and its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoorRef {
+    pub room: String,
+    pub door: String,
+}
+
+/// An item or key in a particular room.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Placed {
+    pub room: String,
+    pub pickup: Pickup,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    /// The save was written by a newer gext, in a format this one doesn't know.
+    TooNew(u32),
+    /// The save doesn't fit the map it's being loaded with.
+    MapMismatch {
+        game_version: String,
+    },
+    /// The world in the save has mistakes that would crash the game, like a door to a missing room.
+    BrokenMap(Vec<Issue>),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "could not open savegame file: {}", err),
+            SaveError::Parse(err) => write!(f, "could not parse savegame file: {}", err),
+            SaveError::TooNew(version) => write!(
+                f,
+                "the savegame is in format {}, but this version of gext only understands up to format {}",
+                version, FORMAT_VERSION
+            ),
+            SaveError::MapMismatch { game_version } => write!(
+                f,
+                "the savegame was made on a different map, or an older version of this one (saved by gext {}); load it with the map it was started on",
+                game_version
+            ),
+            SaveError::BrokenMap(issues) => {
+                write!(f, "the savegame's map is broken: ")?;
+
+                let issues: Vec<String> = issues.iter().map(Issue::to_string).collect();
+                write!(f, "{}", issues.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> SaveError {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> SaveError {
+        SaveError::Parse(err)
+    }
+}
+
+impl SaveFile {
+    /// Saves `player` as changes to `map`, the rooms the game started with. Without the map, the
+    /// whole world is saved instead.
+    pub fn new(player: &Player, map: Option<&HashMap<String, Room>>) -> SaveFile {
+        let (world, snapshot) = match map {
+            Some(map) => (WorldChanges::between(map, &player.map), None),
+            None => (WorldChanges::default(), Some(player.map.clone())),
+        };
+
+        SaveFile {
+            format_version: FORMAT_VERSION,
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            map_fingerprint: map.map(fingerprint),
+            player: SavedPlayer {
+                class: player.class.clone(),
+                name: player.name.clone(),
+                items_held: player.items_held.clone(),
+                keys_held: player.keys_held.clone(),
+                health: player.health,
+                attack: player.attack,
+                defense: player.defense,
+                battles: player.battles.clone(),
+                battles_before_endings: player.battles_before_endings,
+                current_room: player.current_room.clone(),
+                game_name: player.game_name.clone(),
+                rng: player.rng.clone(),
+                turn: player.turn,
+                riddle_failures: player.riddle_failures.clone(),
+                despawned: player.despawned.clone(),
+                ending: player.ending,
+                permadeath: player.permadeath,
+                world,
+                map: snapshot,
+            },
+        }
+    }
+
+    /// Whether the save was made on exactly `map`, as far as its fingerprint tells. Saves without
+    /// one could be from any map.
+    pub fn same_map(&self, map: &HashMap<String, Room>) -> bool {
+        self.map_fingerprint
+            .as_ref()
+            .is_none_or(|found| found == &fingerprint(map))
+    }
+
+    /// Rebuilds the player on `map`.
+    ///
+    /// A save made on an older version of the map still loads, as long as every room, item and
+    /// key it changed is still there. Saves that store the whole world need the exact same map.
+    pub fn restore(self, map: &HashMap<String, Room>) -> Result<Player, SaveError> {
+        let mismatch = || SaveError::MapMismatch {
+            game_version: self.game_version.clone(),
+        };
+
+        let same_map = self.same_map(map);
+
+        let saved = &self.player;
+
+        let world = match &saved.map {
+            Some(_) if !same_map => return Err(mismatch()),
+            Some(snapshot) => {
+                // the ways back through doors used to be added as the player went
+                let mut world = snapshot.clone();
+                repair_doors(&mut world);
+                link_doors(&mut world);
+                world
+            }
+            None => saved.world.apply(map).ok_or_else(mismatch)?,
+        };
+
+        if !world.contains_key(&saved.current_room) {
+            return Err(mismatch());
+        }
+
+        let saved = self.player;
+
+        // old saves can hold a broken copy of the built-in map, so check it before playing on it
+        let world = Map {
+            start_room: saved.current_room.clone(),
+            ..Map::from_rooms(world)
+        };
+
+        let errors: Vec<Issue> = validate_map(&world, std::slice::from_ref(&saved.class))
+            .into_iter()
+            .filter(Issue::is_error)
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(SaveError::BrokenMap(errors));
+        }
+
+        let world = world.rooms;
+
+        Ok(Player {
+            class: saved.class,
+            name: saved.name,
+            current_room: saved.current_room,
+            map: world,
+            items_held: saved.items_held,
+            keys_held: saved.keys_held,
+            health: saved.health,
+            attack: saved.attack,
+            defense: saved.defense,
+            battles: saved.battles,
+            battles_before_endings: saved.battles_before_endings,
+            game_name: saved.game_name,
+            rng: saved.rng,
+            turn: saved.turn,
+            riddle_failures: saved.riddle_failures,
+            despawned: saved.despawned,
+            ending: saved.ending,
+            permadeath: saved.permadeath,
+        })
+    }
+
+    /// Writes to a temporary file first and then renames it over `path`, so a crash part way
+    /// through leaves the old save as it was.
+    pub fn write_to(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(&self)?;
+
+        let temp = path.with_extension("json.tmp");
+
+        let mut file = File::create(&temp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+
+        fs::rename(&temp, path)
+    }
+
+    /// Reads a save of any format version, upgrading it to the current one.
+    pub fn load(path: &Path) -> Result<SaveFile, SaveError> {
+        let value: Value = serde_json::from_reader(File::open(path)?)?;
+
+        Ok(serde_json::from_value(migrate(value)?)?)
+    }
+}
+
+impl WorldChanges {
+    /// What happened to the items, keys and doors of `before` to turn it into `after`.
+    pub fn between(before: &HashMap<String, Room>, after: &HashMap<String, Room>) -> WorldChanges {
+        let mut changes = WorldChanges::default();
+
+        let mut names: Vec<&String> = before.keys().collect();
+        names.sort();
+
+        for name in names {
+            let Some(now) = after.get(name) else {
+                continue;
+            };
+
+            for door in &before[name].doors {
+                let Some(after) = now.doors.iter().find(|d| d.name == door.name) else {
+                    continue;
+                };
+
+                let had_loot = door.enemy.as_ref().is_some_and(Enemy::has_loot);
+
+                if had_loot && !after.enemy.as_ref().is_some_and(Enemy::has_loot) {
+                    changes.looted.push(DoorRef {
+                        room: name.clone(),
+                        door: door.name.clone(),
+                    });
+                }
+
+                let locked = after.locked;
+
+                if locked == door.locked {
+                    continue;
+                }
+
+                let list = if locked {
+                    &mut changes.locked
+                } else {
+                    &mut changes.unlocked
+                };
+
+                list.push(DoorRef {
+                    room: name.clone(),
+                    door: door.name.clone(),
+                });
+            }
+
+            let then = pickups(&before[name]);
+            let now = pickups(now);
+
+            for (from, to, list) in [
+                (&then, &now, &mut changes.removed),
+                (&now, &then, &mut changes.added),
+            ] {
+                let mut rest = to.clone();
+
+                for pickup in from {
+                    match rest.iter().position(|p| p == pickup) {
+                        Some(i) => {
+                            rest.remove(i);
+                        }
+                        None => list.push(Placed {
+                            room: name.clone(),
+                            pickup: pickup.clone(),
+                        }),
+                    }
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// `map` with these changes made to it, or `None` if they don't fit it.
+    pub fn apply(&self, map: &HashMap<String, Room>) -> Option<HashMap<String, Room>> {
+        let mut map = map.clone();
+
+        for placed in &self.removed {
+            let room = map.get_mut(&placed.room)?;
+
+            match &placed.pickup {
+                Pickup::Item(item) => {
+                    let i = room.items.iter().position(|i| i.name == item.name)?;
+                    room.items.remove(i);
+                }
+                Pickup::Key(key) => {
+                    let i = room.keys.iter().position(|k| k.name == key.name)?;
+                    room.keys.remove(i);
+                }
+            }
+        }
+
+        for placed in &self.added {
+            let room = map.get_mut(&placed.room)?;
+
+            match &placed.pickup {
+                Pickup::Item(item) => room.items.push(item.clone()),
+                Pickup::Key(key) => room.keys.push(key.clone()),
+            }
+        }
+
+        for (doors, locked) in [(&self.unlocked, false), (&self.locked, true)] {
+            for door in doors {
+                map.get_mut(&door.room)?
+                    .doors
+                    .iter_mut()
+                    .find(|d| d.name == door.door)?
+                    .locked = locked;
+            }
+        }
+
+        for door in &self.looted {
+            if let Some(enemy) = map
+                .get_mut(&door.room)?
+                .doors
+                .iter_mut()
+                .find(|d| d.name == door.door)?
+                .enemy
+                .as_mut()
+            {
+                enemy.take_loot();
+            }
+        }
+
+        Some(map)
+    }
+}
+
+fn pickups(room: &Room) -> Vec<Pickup> {
+    room.items
+        .iter()
+        .cloned()
+        .map(Pickup::Item)
+        .chain(room.keys.iter().cloned().map(Pickup::Key))
+        .collect()
+}
+
+/// Points doors that lead nowhere at the room they're named after, if there is one. The built-in
+/// map used to send its dungeon doors to a "Dungeon" room that never existed, and old saves keep a
+/// copy of it.
+fn repair_doors(rooms: &mut HashMap<String, Room>) {
+    let names: Vec<String> = rooms.keys().cloned().collect();
+
+    for room in rooms.values_mut() {
+        for door in &mut room.doors {
+            if !names.contains(&door.associated_room_name) && names.contains(&door.name) {
+                door.associated_room_name = door.name.clone();
+            }
+        }
+    }
+}
+
+/// Runs every migration the save needs, oldest first.
+pub fn migrate(mut value: Value) -> Result<Value, SaveError> {
+    // saves from before there was a format version are just the player
+    let mut version = value
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+
+    if version > FORMAT_VERSION {
+        return Err(SaveError::TooNew(version));
+    }
+
+    while version < FORMAT_VERSION {
+        value = MIGRATIONS[version as usize](value);
+        version += 1;
+        value["format_version"] = json!(version);
+    }
+
+    Ok(value)
+}
+
+/// 0 to 1: puts the bare player in an envelope. The map it was started on isn't known.
+fn wrap_bare_player(mut player: Value) -> Value {
+    // games couldn't be lost yet, so the battles already lost don't count towards losing
+    player["battles_before_endings"] = json!(player["battles"].as_array().map_or(0, Vec::len));
+
+    json!({
+        "game_version": "unknown",
+        "map_fingerprint": null,
+        "player": player,
+    })
+}
+
+/// 1 to 2: the player's room is saved by name. The whole map stays in the save, since the map it
+/// was started on isn't to hand to work out what changed.
+fn room_by_name(mut save: Value) -> Value {
+    let player = &mut save["player"];
+
+    player["current_room"] = player["current_room"]["name"].take();
+
+    save
+}
+
+/// A short, stable hash of a map's rooms, used to tell whether a save belongs to a map.
+pub fn fingerprint(rooms: &HashMap<String, Room>) -> String {
+    // rooms and doors are sorted by name here, since serde_json only sorts object keys when its
+    // preserve_order feature is off, which a crate using gext could turn on
+    let mut names: Vec<&String> = rooms.keys().collect();
+    names.sort();
+
+    let mut text = String::new();
+
+    for name in names {
+        let mut room = rooms[name].clone();
+        room.doors.sort_by(|a, b| a.name.cmp(&b.name));
+
+        text.push_str(name);
+        text.push_str(&serde_json::to_string(&room).unwrap_or_default());
+    }
+
+    // 64-bit FNV-1a, which unlike the std hasher is the same on every build
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// A save in the save directory, as shown when choosing one to load.
+#[derive(Debug, Clone)]
+pub struct SaveInfo {
+    /// What to type to load it: the file name without `.save.json`.
+    pub name: String,
+    pub player: String,
+    pub class: String,
+    pub room: String,
+    pub saved_at: SystemTime,
+}
+
+/// Where saves live, and how to get at them by name.
+#[derive(Debug, Clone)]
+pub struct SaveManager {
+    dir: PathBuf,
+}
+
+impl Default for SaveManager {
+    fn default() -> SaveManager {
+        SaveManager::new(default_dir())
+    }
+}
+
+impl SaveManager {
+    pub fn new(dir: impl Into<PathBuf>) -> SaveManager {
+        SaveManager { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file a save is kept in. `name`, `name.save` and `name.save.json` all mean the same save.
+    pub fn path(&self, name: &str) -> PathBuf {
+        let name = name.strip_suffix(".json").unwrap_or(name);
+        let name = name.strip_suffix(".save").unwrap_or(name);
+
+        self.dir.join(format!("{}.save.json", name))
+    }
+
+    /// Saves under `name`, creating the save directory if it isn't there yet.
+    pub fn save(&self, name: &str, save: &SaveFile) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+
+        let path = self.path(name);
+        save.write_to(&path)?;
+
+        Ok(path)
+    }
+
+    /// Saves to the first of `slots` backups of `name`, moving the older ones down a slot and
+    /// dropping the oldest.
+    pub fn autosave(&self, name: &str, save: &SaveFile, slots: usize) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+
+        for slot in (1..slots).rev() {
+            let from = self.path(&backup_name(name, slot));
+
+            if from.exists() {
+                fs::rename(from, self.path(&backup_name(name, slot + 1)))?;
+            }
+        }
+
+        self.save(&backup_name(name, 1), save)
+    }
+
+    /// Deletes the first `slots` backups of `name`.
+    pub fn delete_backups(&self, name: &str, slots: usize) -> std::io::Result<()> {
+        for slot in 1..=slots {
+            match fs::remove_file(self.path(&backup_name(name, slot))) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str) -> Result<SaveFile, SaveError> {
+        SaveFile::load(&self.path(name))
+    }
+
+    pub fn delete(&self, name: &str) -> std::io::Result<()> {
+        fs::remove_file(self.path(name))
+    }
+
+    /// Every save that can be read, newest first. A missing save directory just means no saves.
+    pub fn list(&self) -> std::io::Result<Vec<SaveInfo>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+
+        let mut saves = vec![];
+
+        for entry in entries {
+            let path = entry?.path();
+
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".save.json"))
+            else {
+                continue;
+            };
+
+            let Ok(save) = SaveFile::load(&path) else {
+                continue;
+            };
+
+            saves.push(SaveInfo {
+                name: name.to_string(),
+                player: save.player.name,
+                class: save.player.class.name,
+                room: save.player.current_room,
+                saved_at: fs::metadata(&path)?.modified()?,
+            });
+        }
+
+        saves.sort_by_key(|save| std::cmp::Reverse(save.saved_at));
+
+        Ok(saves)
+    }
+}
+
+/// The name a backup of `name` is saved under: `name.auto1` is the newest.
+pub fn backup_name(name: &str, slot: usize) -> String {
+    format!("{}.auto{}", name, slot)
+}
+
+/// `$XDG_DATA_HOME/gext/savegames`, falling back to `~/.local/share`, or `%APPDATA%` on Windows.
+/// Without any of those, saves go in `savegames` in the working directory, as they used to.
+pub fn default_dir() -> PathBuf {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from));
+
+    match data_dir {
+        Some(dir) => dir.join("gext").join("savegames"),
+        None => PathBuf::from("savegames"),
+    }
+}
+
+/// Formats a time as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_time(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let days = (seconds / 86400) as i64;
+    let minutes = (seconds % 86400) / 60;
+
+    // days since 1970 to a calendar date, from Howard Hinnant's `civil_from_days`
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
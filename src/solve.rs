@@ -0,0 +1,704 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::behaviour::{Action, Situation};
+use crate::damage::{percent_of, Rules};
+use crate::engine::Stats;
+use crate::riddles::WrongAnswerPolicy;
+use crate::structs::{Class, Door, Enemy, Item, Key, Lock, Room, RoomRequirements, SkillEffect};
+
+/// Battles that go on longer than this are counted as lost.
+const MAX_ROUNDS: u32 = 1000;
+
+/// Why a door couldn't be got through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Blocker {
+    Locked(Lock),
+    /// The door is locked, but has no key.
+    NoKey,
+    Requirements(RoomRequirements),
+    /// The enemy is too strong to beat.
+    Guarded(String),
+}
+
+/// A door out of a reachable room that couldn't be opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedDoor {
+    pub room: String,
+    pub door: String,
+    pub blocker: Blocker,
+}
+
+/// How a key can be lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyLoss {
+    /// To a wrong riddle answer, when picking it up.
+    Riddle,
+    /// By unlocking this door with it, which uses it up.
+    Spent { door: String },
+}
+
+/// A key that, if lost, leaves rooms out of reach for good.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoftLock {
+    /// The room the key lies in, or for a key spent on a door, the room the door is in.
+    pub room: String,
+    pub key: Key,
+    pub loss: KeyLoss,
+    /// The rooms that can no longer be reached.
+    pub rooms: Vec<String>,
+}
+
+/// How far one class can get through a map, found by [`solve`].
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub class: String,
+    pub reachable: Vec<String>,
+    pub unreachable: Vec<String>,
+    pub blocked: Vec<BlockedDoor>,
+    pub soft_locks: Vec<SoftLock>,
+}
+
+impl Solution {
+    pub fn is_complete(&self) -> bool {
+        self.unreachable.is_empty()
+    }
+}
+
+impl fmt::Display for Blocker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Blocker::Locked(lock) => write!(f, "locked with {}", lock),
+            Blocker::NoKey => write!(f, "locked, with no key"),
+            Blocker::Requirements(requirements) => write!(
+                f,
+                "needs {} health and {} attack",
+                requirements.health, requirements.attack
+            ),
+            Blocker::Guarded(enemy) => write!(f, "guarded by \"{}\", who is too strong", enemy),
+        }
+    }
+}
+
+impl fmt::Display for BlockedDoor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the \"{}\" door in the \"{}\" is {}",
+            self.door, self.room, self.blocker
+        )
+    }
+}
+
+impl fmt::Display for SoftLock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.loss {
+            KeyLoss::Riddle => write!(
+                f,
+                "losing the \"{}\" key in the \"{}\" to a wrong answer",
+                self.key.name, self.room
+            )?,
+            KeyLoss::Spent { door } => write!(
+                f,
+                "spending the \"{}\" key on the \"{}\" door in the \"{}\"",
+                self.key.name, door, self.room
+            )?,
+        }
+
+        write!(
+            f,
+            " cuts off {}",
+            self.rooms
+                .iter()
+                .map(|room| format!("\"{}\"", room))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Plays through a map as `class`, to see which rooms can be reached.
+///
+/// Every riddle is assumed to be answered correctly, and battles are fought with average rolls
+/// under `rules`. Items are used outside battle (at half strength) only to meet a door's
+/// requirements, and in battle only when the fight can't be won without them.
+///
+/// Single-use keys are never used up, so a room counts as reachable if any door a key fits leads
+/// there. Each single-use key is then spent in turn on each door it opened, to find the choices
+/// that cut rooms off. If `policy` lets keys be lost for good, each key is also taken away in turn
+/// to find soft-locks.
+pub fn solve(
+    rooms: &HashMap<String, Room>,
+    start_room: &str,
+    class: &Class,
+    policy: &WrongAnswerPolicy,
+    rules: &Rules,
+) -> Solution {
+    let run = Run::new(rooms, start_room, class, rules, WhatIf::Nothing);
+
+    let mut soft_locks = vec![];
+
+    let mut soft_lock = |room: &String, key: &Key, loss: KeyLoss, what_if: WhatIf| {
+        let without = Run::new(rooms, start_room, class, rules, what_if);
+
+        let lost: Vec<String> = run
+            .visited
+            .iter()
+            .filter(|name| !without.visited.contains(*name))
+            .cloned()
+            .collect();
+
+        if !lost.is_empty() {
+            soft_locks.push(SoftLock {
+                room: room.clone(),
+                key: key.clone(),
+                loss,
+                rooms: lost,
+            });
+        }
+    };
+
+    if policy.despawn_keys && policy.respawn_after.is_none() {
+        for (room, key) in &run.keys_taken {
+            soft_lock(room, key, KeyLoss::Riddle, WhatIf::Lost { room, key });
+        }
+    }
+
+    for (room, door, key) in &run.keys_spent {
+        soft_lock(
+            room,
+            key,
+            KeyLoss::Spent { door: door.clone() },
+            WhatIf::Spent { room, door, key },
+        );
+    }
+
+    let mut unreachable: Vec<String> = rooms
+        .keys()
+        .filter(|name| !run.visited.contains(*name))
+        .cloned()
+        .collect();
+    unreachable.sort();
+
+    Solution {
+        class: class.name.clone(),
+        reachable: run.visited,
+        unreachable,
+        blocked: run.blocked,
+        soft_locks,
+    }
+}
+
+/// How a playthrough differs from the best case.
+#[derive(Clone, Copy)]
+enum WhatIf<'a> {
+    Nothing,
+    /// The key in this room is lost to a wrong answer, so it's never picked up.
+    Lost {
+        room: &'a String,
+        key: &'a Key,
+    },
+    /// The single-use key is spent on this door as soon as the door can be reached, and is gone
+    /// after. Other doors it fits wait until then.
+    Spent {
+        room: &'a String,
+        door: &'a String,
+        key: &'a Key,
+    },
+}
+
+/// One greedy playthrough.
+struct Run {
+    /// Rooms in the order they were reached.
+    visited: Vec<String>,
+    blocked: Vec<BlockedDoor>,
+    /// Keys picked up from rooms, in the order they were taken.
+    keys_taken: Vec<(String, Key)>,
+    /// Single-use keys that unlocked doors, by the room and name of the door.
+    keys_spent: Vec<(String, String, Key)>,
+}
+
+impl Run {
+    fn new(
+        rooms: &HashMap<String, Room>,
+        start_room: &str,
+        class: &Class,
+        rules: &Rules,
+        what_if: WhatIf,
+    ) -> Run {
+        let mut stats = Stats {
+            health: class.health,
+            attack: class.attack,
+            defense: class.defense,
+        };
+        let mut items: Vec<Item> = class.starting_items.clone();
+        let mut keys: Vec<Key> = class.starting_keys.clone();
+        let mut defeated: HashSet<String> = HashSet::new();
+        // doors stay unlocked once opened, even if the key was used up
+        let mut unlocked: HashSet<(String, String)> = HashSet::new();
+
+        let mut visited: Vec<String> = vec![];
+        let mut keys_taken = vec![];
+        let mut keys_spent = vec![];
+
+        // until the key is spent on its door, or that door turns out to be out of reach
+        let mut saving_key = matches!(what_if, WhatIf::Spent { .. });
+
+        if rooms.contains_key(start_room) {
+            visited.push(start_room.to_string());
+        }
+
+        let mut explored = 0;
+
+        // keep going over every reachable door until nothing new opens up,
+        // since a key or a won battle can open a door in a room visited earlier
+        loop {
+            while explored < visited.len() {
+                let room = &rooms[&visited[explored]];
+                explored += 1;
+
+                items.extend(room.items.iter().cloned());
+
+                for key in &room.keys {
+                    if let WhatIf::Lost {
+                        room: lost,
+                        key: lost_key,
+                    } = what_if
+                    {
+                        if lost == &room.name && lost_key == key {
+                            continue;
+                        }
+                    }
+
+                    keys.push(key.clone());
+                    keys_taken.push((room.name.clone(), key.clone()));
+                }
+            }
+
+            let mut progressed = false;
+            let mut waited = false;
+            let mut blocked = vec![];
+
+            for name in visited.clone() {
+                for door in exits(rooms, &name) {
+                    let target = &door.associated_room_name;
+
+                    if visited.contains(target) {
+                        continue;
+                    }
+
+                    let mut used = vec![];
+
+                    if door.locked && !unlocked.contains(&(name.clone(), door.name.clone())) {
+                        let Some(found) = door.key.as_ref().and_then(|lock| lock.find(&keys))
+                        else {
+                            blocked.push(BlockedDoor {
+                                room: name.clone(),
+                                door: door.name.clone(),
+                                blocker: match &door.key {
+                                    Some(lock) => Blocker::Locked(lock.clone()),
+                                    None => Blocker::NoKey,
+                                },
+                            });
+                            continue;
+                        };
+
+                        used = found;
+                    }
+
+                    let spends = match what_if {
+                        WhatIf::Spent {
+                            room,
+                            door: spent_on,
+                            key,
+                        } => used
+                            .iter()
+                            .position(|&i| &keys[i] == key)
+                            .map(|i| (room == &name && spent_on == &door.name, i)),
+                        _ => None,
+                    };
+
+                    if saving_key && spends.is_some_and(|(here, _)| !here) {
+                        waited = true;
+                        continue;
+                    }
+
+                    if let Some(requirements) = &door.requirements {
+                        if !meet_requirements(&mut stats, &mut items, requirements) {
+                            blocked.push(BlockedDoor {
+                                room: name.clone(),
+                                door: door.name.clone(),
+                                blocker: Blocker::Requirements(requirements.clone()),
+                            });
+                            continue;
+                        }
+                    }
+
+                    if door.locked && !unlocked.contains(&(name.clone(), door.name.clone())) {
+                        for &i in &used {
+                            let spent = (name.clone(), door.name.clone(), keys[i].clone());
+
+                            if keys[i].single_use && !keys_spent.contains(&spent) {
+                                keys_spent.push(spent);
+                            }
+                        }
+
+                        // only the key the run is about is used up
+                        if let Some((true, i)) = spends {
+                            keys.remove(used[i]);
+                            saving_key = false;
+                        }
+
+                        unlocked.insert((name.clone(), door.name.clone()));
+                    }
+
+                    if let Some(enemy) = door.enemy.as_ref().filter(|e| !defeated.contains(&e.name))
+                    {
+                        if !fight(class, rules, &mut stats, &mut items, &mut keys, enemy) {
+                            blocked.push(BlockedDoor {
+                                room: name.clone(),
+                                door: door.name.clone(),
+                                blocker: Blocker::Guarded(enemy.name.clone()),
+                            });
+                            continue;
+                        }
+
+                        defeated.insert(enemy.name.clone());
+                    }
+
+                    if !visited.contains(target) {
+                        visited.push(target.clone());
+                    }
+                    progressed = true;
+                }
+            }
+
+            // the door the key is being saved for can't be reached, so the key goes elsewhere
+            if !progressed && waited {
+                saving_key = false;
+                continue;
+            }
+
+            if !progressed {
+                return Run {
+                    visited,
+                    blocked,
+                    keys_taken,
+                    keys_spent,
+                };
+            }
+        }
+    }
+}
+
+/// The doors out of a room that lead somewhere in the map.
+pub(crate) fn exits<'a>(rooms: &'a HashMap<String, Room>, name: &str) -> Vec<&'a Door> {
+    rooms.get(name).map_or(vec![], |room| {
+        room.doors
+            .iter()
+            .filter(|door| rooms.contains_key(&door.associated_room_name))
+            .collect()
+    })
+}
+
+/// Uses items outside battle, strongest first, until the requirements are met. Nothing is used
+/// if they can't be met.
+fn meet_requirements(
+    stats: &mut Stats,
+    items: &mut Vec<Item>,
+    requirements: &RoomRequirements,
+) -> bool {
+    let met =
+        |stats: &Stats| stats.health >= requirements.health && stats.attack >= requirements.attack;
+
+    let mut boosted = *stats;
+    let mut used = vec![];
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| {
+        Reverse(
+            items[i]
+                .health
+                .max(0)
+                .saturating_add(items[i].attack.max(0)),
+        )
+    });
+
+    for i in order {
+        if met(&boosted) {
+            break;
+        }
+
+        let item = &items[i];
+
+        if item.health / 2 <= 0 && item.attack / 2 <= 0 {
+            continue;
+        }
+
+        boosted.health = boosted.health.saturating_add(item.health / 2);
+        boosted.attack = boosted.attack.saturating_add(item.attack / 2);
+        used.push(i);
+    }
+
+    if !met(&boosted) {
+        return false;
+    }
+
+    *stats = boosted;
+    used.sort();
+
+    for i in used.into_iter().rev() {
+        items.remove(i);
+    }
+
+    true
+}
+
+/// Fights `enemy`, first without items and then with every helpful item, and applies the winnings.
+///
+/// Only the enemy's keys and guaranteed loot are counted, since it may use up its own items and
+/// random drops might not drop.
+fn fight(
+    class: &Class,
+    rules: &Rules,
+    stats: &mut Stats,
+    items: &mut Vec<Item>,
+    keys: &mut Vec<Key>,
+    enemy: &Enemy,
+) -> bool {
+    let helpful: Vec<Item> = items
+        .iter()
+        .filter(|i| i.health > 0 || i.attack > 0 || i.defense > 0)
+        .cloned()
+        .collect();
+
+    let boost = |stat: i32, of: fn(&Item) -> i32| {
+        helpful
+            .iter()
+            .fold(stat, |sum, i| sum.saturating_add(of(i).max(0)))
+    };
+
+    let boosted = Stats {
+        health: boost(stats.health, |i| i.health),
+        attack: boost(stats.attack, |i| i.attack),
+        defense: boost(stats.defense, |i| i.defense),
+    };
+
+    let fled = match simulate(class, rules, *stats, enemy, 0) {
+        Some(fled) => fled,
+        None => match simulate(class, rules, boosted, enemy, helpful.len() as u32) {
+            Some(fled) => {
+                items.retain(|i| !helpful.contains(i));
+                fled
+            }
+            None => return false,
+        },
+    };
+
+    if !fled {
+        stats.health = stats
+            .health
+            .saturating_add(enemy.health)
+            .saturating_add(class.won_battle_health_bonus);
+        stats.attack = stats
+            .attack
+            .saturating_add(enemy.attack)
+            .saturating_add(class.won_battle_attack_bonus);
+        stats.defense = stats
+            .defense
+            .saturating_add(enemy.defense)
+            .saturating_add(class.won_battle_defense_bonus);
+
+        items.extend(enemy.loot.items.iter().cloned());
+        keys.extend(enemy.keys.iter().chain(&enemy.loot.keys).cloned());
+    }
+
+    true
+}
+
+/// Whether `class` with `stats` beats `enemy`, if every roll comes out average.
+pub fn wins_battle(class: &Class, rules: &Rules, stats: Stats, enemy: &Enemy) -> bool {
+    simulate(class, rules, stats, enemy, 0).is_some()
+}
+
+/// What the player does with a round of a simulated battle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Move {
+    /// Strikes, with a plain attack or a skill, for this much damage.
+    Strike(f64),
+    UseItem,
+    Heal(i32),
+    Stun,
+}
+
+/// Plays out a battle with average rolls, following the enemy's [`Behaviour`] but not its items.
+/// The player spends the first `item_rounds` rounds using items instead of attacking, and then
+/// uses skills as [`choose_skill`] says. Gives whether the enemy fled if the player wins, or `None`
+/// if they lose.
+///
+/// [`Behaviour`]: crate::behaviour::Behaviour
+fn simulate(
+    class: &Class,
+    rules: &Rules,
+    stats: Stats,
+    enemy: &Enemy,
+    item_rounds: u32,
+) -> Option<bool> {
+    let dodge = 1.0 - class.dodge_chance() as f64 / 100.0;
+
+    let mut health = stats.health as f64;
+    let mut enemy_health = enemy.health as f64;
+    let mut revived = false;
+    let mut heals = 0;
+    let mut uses: Vec<u32> = class.skills.iter().map(|skill| skill.uses).collect();
+
+    for round in 1..=MAX_ROUNDS {
+        let situation = Situation {
+            round,
+            health: enemy_health.ceil() as i32,
+            max_health: enemy.health,
+            heals,
+            enemy: enemy.stats(),
+            player: stats,
+        };
+
+        let behaviour = enemy.behaviour;
+        let action = behaviour.action(&situation);
+
+        let defense = percent_of(
+            enemy.defense,
+            behaviour.defense_percent(&situation, action).into(),
+        );
+        let attack = percent_of(enemy.attack, behaviour.attack_percent(&situation).into());
+
+        let strike = |percent: u32, pierce: bool| {
+            let defense = if pierce { 0 } else { defense };
+            rules.average_damage(stats.attack, defense, percent, class.crit())
+        };
+        let taken = match action {
+            Action::Attack => rules.average_damage(attack, stats.defense, 100, None) * dodge,
+            _ => 0.0,
+        };
+
+        let player_move = if round <= item_rounds {
+            Move::UseItem
+        } else {
+            match choose_skill(class, &uses, &strike, stats, health, enemy_health, taken) {
+                Some((i, player_move)) => {
+                    uses[i] -= 1;
+                    player_move
+                }
+                None => Move::Strike(strike(100, false)),
+            }
+        };
+
+        let stunned = player_move == Move::Stun;
+
+        if let Move::Heal(amount) = player_move {
+            health += amount as f64;
+        }
+
+        match action {
+            _ if stunned => {}
+            Action::Flee => return Some(true),
+            Action::Heal(amount) => {
+                heals += 1;
+                enemy_health = (enemy_health + amount as f64).min(enemy.health as f64);
+            }
+            Action::Defend | Action::Attack => {}
+        }
+
+        for player_turn in [class.has_first_strike(), !class.has_first_strike()] {
+            if player_turn {
+                let Move::Strike(dealt) = player_move else {
+                    continue;
+                };
+
+                enemy_health -= dealt;
+                health += dealt * class.lifesteal_percent() as f64 / 100.0;
+
+                if enemy_health <= 0.0 {
+                    return Some(false);
+                }
+            } else if action == Action::Attack && !stunned {
+                health -= taken;
+
+                if health <= 0.0 {
+                    if !revived && class.can_revive() {
+                        revived = true;
+                        health = stats.health as f64;
+                        continue;
+                    }
+
+                    return None;
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// The skill worth using this round, if any, and what it does: a strike that finishes the enemy
+/// off, a stun or heal to live through a hit that would otherwise be the end, or the strongest
+/// strike if it beats a plain attack. `taken` is the damage the enemy is about to do.
+fn choose_skill(
+    class: &Class,
+    uses: &[u32],
+    strike: &dyn Fn(u32, bool) -> f64,
+    stats: Stats,
+    health: f64,
+    enemy_health: f64,
+    taken: f64,
+) -> Option<(usize, Move)> {
+    let mut best_strike: Option<(usize, f64)> = None;
+    let mut heal = None;
+    let mut stun = None;
+
+    for (i, skill) in class.skills.iter().enumerate() {
+        if uses[i] == 0 {
+            continue;
+        }
+
+        match skill.effect {
+            SkillEffect::Strike { percent, pierce } => {
+                let damage = strike(percent, pierce);
+
+                if best_strike.is_none_or(|(_, best)| damage > best) {
+                    best_strike = Some((i, damage));
+                }
+            }
+            SkillEffect::Heal { percent } => {
+                heal.get_or_insert((i, percent_of(stats.health, percent.into())));
+            }
+            SkillEffect::Stun => {
+                stun.get_or_insert(i);
+            }
+        }
+    }
+
+    let plain = strike(100, false);
+
+    if plain >= enemy_health {
+        return None;
+    }
+
+    if let Some((i, damage)) = best_strike.filter(|(_, damage)| *damage >= enemy_health) {
+        return Some((i, Move::Strike(damage)));
+    }
+
+    if health - taken <= 0.0 {
+        if let Some(i) = stun {
+            return Some((i, Move::Stun));
+        }
+
+        if let Some((i, amount)) = heal.filter(|(_, amount)| health + *amount as f64 > taken) {
+            return Some((i, Move::Heal(amount)));
+        }
+    }
+
+    best_strike
+        .filter(|(_, damage)| *damage > plain)
+        .map(|(i, damage)| (i, Move::Strike(damage)))
+}
@@ -1,6 +1,10 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
+use crate::behaviour::Behaviour;
+use crate::engine::Stats;
+use crate::rng::GameRng;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct RoomRequirements {
     pub health: i32,
@@ -8,9 +12,30 @@ pub struct RoomRequirements {
     pub defense: i32,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default, Serialize, Deserialize)]
 pub struct Key {
     pub name: String,
+    /// Used up once it has unlocked a door.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub single_use: bool,
+    /// The names of other keys this one also does the job of, making it a master key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub opens: Vec<String>,
+}
+
+/// The keys that open a door.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Lock {
+    /// Any one of these keys.
+    Any {
+        any: Vec<Key>,
+    },
+    /// All of these keys together.
+    All {
+        all: Vec<Key>,
+    },
+    One(Key),
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -27,13 +52,104 @@ pub struct Door {
     pub name: String,
     pub description: String,
     pub locked: bool,
-    pub key: Key,
+    /// `None` for a door with no key, which can't be locked.
+    #[serde(default, deserialize_with = "lock")]
+    pub key: Option<Lock>,
 
-    pub enemy: Option<Player>,
+    pub enemy: Option<Enemy>,
 
     pub associated_room_name: String,
 
     pub requirements: Option<RoomRequirements>,
+
+    /// Whether the door only goes one way. Otherwise the room it leads to gets a door back, unless
+    /// it has one of its own. See [`link_doors`](crate::map::link_doors).
+    #[serde(default)]
+    pub one_way: bool,
+    /// How the way back through a two-way door is locked and guarded. Without it, it's open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub back: Option<DoorSide>,
+}
+
+/// Guards a door, and has to be beaten to get through it.
+///
+/// Older maps give enemies everything a [`Player`] has; the fields an enemy doesn't need are
+/// ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enemy {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub health: i32,
+    pub attack: i32,
+    pub defense: i32,
+    /// Items the enemy might use in battle.
+    #[serde(default, alias = "items_held", skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Item>,
+    /// Keys the enemy carries.
+    #[serde(default, alias = "keys_held", skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<Key>,
+    /// What it drops when it's beaten, on top of its keys and the items it didn't use.
+    #[serde(default, skip_serializing_if = "Loot::is_empty")]
+    pub loot: Loot,
+    #[serde(default)]
+    pub dialogue: Dialogue,
+    #[serde(default)]
+    pub behaviour: Behaviour,
+}
+
+/// An enemy's drop tables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Loot {
+    /// Always dropped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Item>,
+    /// Always dropped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<Key>,
+    /// Each rolled for separately.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub random: Vec<RandomDrop>,
+}
+
+/// An item or key that's dropped with a chance, as a percentage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomDrop {
+    pub chance: u32,
+    #[serde(flatten)]
+    pub pickup: Pickup,
+}
+
+/// What an enemy says during a battle. Each line is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dialogue {
+    /// When the battle starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    /// When the player beats it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defeated: Option<String>,
+    /// When it beats the player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub victorious: Option<String>,
+}
+
+/// The way back through a two-way door, set up separately from the way there.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DoorSide {
+    /// Defaults to the name of the room the door leads back to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, deserialize_with = "lock")]
+    pub key: Option<Lock>,
+    #[serde(default)]
+    pub enemy: Option<Enemy>,
+    #[serde(default)]
+    pub requirements: Option<RoomRequirements>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,10 +175,85 @@ pub struct Player {
     pub defense: i32,
 
     pub battles: Vec<BattleResult>,
+    /// How many of `battles` were fought in a version of gext where losing didn't end the game.
+    /// They don't count towards a lose condition.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub battles_before_endings: usize,
 
-    pub current_room: Room,
+    /// The name of the room the player is in, in `map`.
+    pub current_room: String,
 
     pub game_name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng: Option<GameRng>,
+
+    /// How many moves the player has made: commands that went through and changed the world, such
+    /// as going through a door or taking an item, but not looking around or saving. Used to time
+    /// respawns.
+    #[serde(default)]
+    pub turn: u64,
+    /// Wrong answers so far for each item or key still lying around, by [`Pickup::id`].
+    #[serde(default)]
+    pub riddle_failures: HashMap<String, u32>,
+    #[serde(default)]
+    pub despawned: Vec<Despawned>,
+
+    /// Set once the game is over, so a finished game can't be loaded and carried on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ending: Option<Ending>,
+    /// Losing the game marks the save as over, so it can't be reloaded.
+    #[serde(default)]
+    pub permadeath: bool,
+}
+
+pub(crate) fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Reads a door's key, where older maps give doors with no key a key with no name.
+fn lock<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Lock>, D::Error> {
+    Ok(Option::<Lock>::deserialize(deserializer)?.and_then(Lock::without_blanks))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ending {
+    Won,
+    Lost,
+}
+
+/// The final score screen, built from the player by [`Player::summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub ending: Option<Ending>,
+    pub name: String,
+    pub class: String,
+    pub turns: u64,
+    pub battles_won: usize,
+    pub battles_lost: usize,
+    /// Enemies beaten, in the order they were beaten.
+    pub defeated: Vec<String>,
+    pub stats: Stats,
+    pub items: usize,
+    pub keys: usize,
+    pub score: i64,
+}
+
+/// Something in a room that can be picked up by answering a riddle.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pickup {
+    Item(Item),
+    Key(Key),
+}
+
+/// An item or key that was lost to a wrong answer, and is coming back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Despawned {
+    pub room: String,
+    pub pickup: Pickup,
+    pub returns_on_turn: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +263,9 @@ pub struct BattleResult {
     pub enemy_health: i32,
 
     pub enemy_name: String,
+    /// The enemy ran away. This counts as a win, but not as defeating it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,10 +276,75 @@ pub struct Class {
     pub attack: i32,
     pub defense: i32,
 
+    #[serde(default)]
     pub starting_items: Vec<Item>,
+    #[serde(default)]
     pub starting_keys: Vec<Key>,
 
+    #[serde(default)]
     pub won_battle_attack_bonus: i32,
+    #[serde(default)]
     pub won_battle_defense_bonus: i32,
+    #[serde(default)]
     pub won_battle_health_bonus: i32,
+
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    /// Actions the class can choose in battle, besides the ones every class has.
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+}
+
+/// A special rule a class brings into every battle. Chances are percentages.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Ability {
+    /// The first time the player would die in a battle, they get back up with the health they started it with.
+    ReviveOnce,
+    /// Heals the player by this percentage of the damage they deal.
+    Lifesteal {
+        percent: u32,
+    },
+    /// The player strikes before the enemy each round.
+    FirstStrike,
+    Dodge {
+        chance: u32,
+    },
+    /// `multiplier` is a percentage of normal damage, so 200 is double damage.
+    Crit {
+        chance: u32,
+        multiplier: u32,
+    },
+}
+
+/// An action a class can choose in battle, in place of attacking.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Skill {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// How many times it can be used in each battle.
+    #[serde(default = "default_uses")]
+    pub uses: u32,
+    pub effect: SkillEffect,
+}
+
+fn default_uses() -> u32 {
+    1
+}
+
+/// What a [`Skill`] does. Percentages are of the usual amount.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SkillEffect {
+    /// Strikes for `percent` of the usual damage, ignoring the enemy's defense if `pierce` is set.
+    Strike {
+        percent: u32,
+        #[serde(default)]
+        pierce: bool,
+    },
+    /// Heals the player by `percent` of the health they started the battle with.
+    Heal { percent: u32 },
+    /// The enemy loses its next turn.
+    Stun,
 }
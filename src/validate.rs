@@ -0,0 +1,350 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::map::{Map, WinCondition};
+use crate::solve::exits;
+use crate::structs::{Class, Enemy, Item, Key, Lock, Pickup, Room};
+
+/// Something wrong with a map, found by [`validate_map`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// A room is stored under one name but calls itself another.
+    NameMismatch {
+        key: String,
+        name: String,
+    },
+    MissingStartRoom(String),
+    /// A door leads to a room that isn't in the map.
+    DanglingDoor {
+        room: String,
+        door: String,
+        target: String,
+    },
+    DuplicateDoor {
+        room: String,
+        door: String,
+    },
+    /// A locked door needs keys that can't be found anywhere. `lock` is just the missing ones.
+    UnobtainableKey {
+        room: String,
+        door: String,
+        lock: Lock,
+    },
+    /// A door is locked, but has no key to open it with.
+    LockedWithoutKey {
+        room: String,
+        door: String,
+    },
+    /// The win condition asks for a room that isn't in the map.
+    UnknownWinRoom(String),
+    /// The win condition asks for an item that isn't in any room, dropped by any enemy or given to
+    /// any class.
+    UnknownWinItem(String),
+    /// The win condition asks for an enemy that doesn't guard any door.
+    UnknownWinEnemy(String),
+    /// No chain of doors, including the ways back through two-way doors, leads from the start room
+    /// here.
+    UnreachableRoom(String),
+    /// One of the map's damage `rules`, or its `wrong_answer` policy, is out of range.
+    BadRule {
+        rule: &'static str,
+        reason: &'static str,
+    },
+}
+
+impl Issue {
+    /// Errors crash the game or ask for something impossible; everything else is a warning.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Issue::NameMismatch { .. }
+                | Issue::MissingStartRoom(_)
+                | Issue::DanglingDoor { .. }
+                | Issue::BadRule { .. }
+        )
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::NameMismatch { key, name } => write!(
+                f,
+                "the room stored as \"{}\" is named \"{}\"",
+                key, name
+            ),
+            Issue::MissingStartRoom(room) => {
+                write!(f, "the start room \"{}\" is not in the map", room)
+            }
+            Issue::DanglingDoor { room, door, target } => write!(
+                f,
+                "the \"{}\" door in the \"{}\" leads to \"{}\", which is not in the map",
+                door, room, target
+            ),
+            Issue::DuplicateDoor { room, door } => write!(
+                f,
+                "the \"{}\" has more than one door called \"{}\"",
+                room, door
+            ),
+            Issue::UnobtainableKey { room, door, lock } => write!(
+                f,
+                "the \"{}\" door in the \"{}\" is locked with {}, which can't be found in the map or any class",
+                door, room, lock
+            ),
+            Issue::LockedWithoutKey { room, door } => write!(
+                f,
+                "the \"{}\" door in the \"{}\" is locked, but has no key",
+                door, room
+            ),
+            Issue::UnknownWinRoom(room) => write!(
+                f,
+                "the map is won by reaching the \"{}\", which is not in the map",
+                room
+            ),
+            Issue::UnknownWinItem(item) => write!(
+                f,
+                "the map is won by holding the \"{}\" item, which is nowhere in the map or any class",
+                item
+            ),
+            Issue::UnknownWinEnemy(enemy) => write!(
+                f,
+                "the map is won by defeating \"{}\", who doesn't guard any door",
+                enemy
+            ),
+            Issue::BadRule { rule, reason } => write!(f, "the map's \"{}\" rule {}", rule, reason),
+            Issue::UnreachableRoom(room) => write!(
+                f,
+                "the \"{}\" can't be reached from the start room",
+                room
+            ),
+        }
+    }
+}
+
+/// The enemies guarding the doors out of `room`.
+fn enemies(room: &Room) -> impl Iterator<Item = &Enemy> {
+    room.doors.iter().filter_map(|d| d.enemy.as_ref())
+}
+
+/// Every item `enemy` might drop when it's beaten.
+fn dropped_items(enemy: &Enemy) -> impl Iterator<Item = &Item> {
+    enemy
+        .items
+        .iter()
+        .chain(&enemy.loot.items)
+        .chain(
+            enemy
+                .loot
+                .random
+                .iter()
+                .filter_map(|drop| match &drop.pickup {
+                    Pickup::Item(item) => Some(item),
+                    Pickup::Key(_) => None,
+                }),
+        )
+}
+
+/// Checks a map for mistakes that serde can't catch.
+pub fn validate_map(map: &Map, classes: &[Class]) -> Vec<Issue> {
+    let rooms = &map.rooms;
+    let start_room = map.start_room.as_str();
+
+    let mut issues = vec![];
+
+    let mut names: Vec<&String> = rooms.keys().collect();
+    names.sort();
+
+    for key in &names {
+        let room = &rooms[*key];
+
+        if &room.name != *key {
+            issues.push(Issue::NameMismatch {
+                key: key.to_string(),
+                name: room.name.clone(),
+            });
+        }
+    }
+
+    if !rooms.contains_key(start_room) {
+        issues.push(Issue::MissingStartRoom(start_room.to_string()));
+    }
+
+    let rules = &map.rules;
+
+    for (broken, rule, reason) in [
+        (rules.variance > 100, "variance", "can't be more than 100%"),
+        (
+            rules.crit_chance > 100,
+            "crit_chance",
+            "can't be more than 100%",
+        ),
+        (
+            rules.crit_multiplier < 100,
+            "crit_multiplier",
+            "can't be less than 100%, or critical hits would do less damage than other hits",
+        ),
+    ] {
+        if broken {
+            issues.push(Issue::BadRule { rule, reason });
+        }
+    }
+
+    if let Some(policy) = &map.wrong_answer {
+        for (broken, rule, reason) in [
+            (
+                policy.attempts == 0,
+                "wrong_answer.attempts",
+                "must be at least 1",
+            ),
+            (
+                policy.health_cost < 0,
+                "wrong_answer.health_cost",
+                "can't be negative",
+            ),
+        ] {
+            if broken {
+                issues.push(Issue::BadRule { rule, reason });
+            }
+        }
+    }
+
+    match &map.win_condition {
+        Some(WinCondition::ReachRoom { room }) if !rooms.contains_key(room) => {
+            issues.push(Issue::UnknownWinRoom(room.clone()));
+        }
+        Some(WinCondition::HoldItems { items }) => {
+            for item in items {
+                let exists = rooms.values().any(|r| {
+                    r.items.iter().any(|i| &i.name == item)
+                        || enemies(r).any(|e| dropped_items(e).any(|i| &i.name == item))
+                }) || classes
+                    .iter()
+                    .any(|c| c.starting_items.iter().any(|i| &i.name == item));
+
+                if !exists {
+                    issues.push(Issue::UnknownWinItem(item.clone()));
+                }
+            }
+        }
+        Some(WinCondition::DefeatEnemy { enemy }) => {
+            let exists = rooms.values().any(|r| {
+                r.doors
+                    .iter()
+                    .any(|d| d.enemy.as_ref().is_some_and(|e| &e.name == enemy))
+            });
+
+            if !exists {
+                issues.push(Issue::UnknownWinEnemy(enemy.clone()));
+            }
+        }
+        _ => {}
+    }
+
+    let mut available_keys: Vec<&Key> = vec![];
+
+    for room in rooms.values() {
+        available_keys.extend(&room.keys);
+
+        for enemy in enemies(room) {
+            available_keys.extend(&enemy.keys);
+            available_keys.extend(&enemy.loot.keys);
+            available_keys.extend(
+                enemy
+                    .loot
+                    .random
+                    .iter()
+                    .filter_map(|drop| match &drop.pickup {
+                        Pickup::Key(key) => Some(key),
+                        Pickup::Item(_) => None,
+                    }),
+            );
+        }
+    }
+
+    for class in classes {
+        available_keys.extend(&class.starting_keys);
+    }
+
+    for key in &names {
+        let room = &rooms[*key];
+        let mut door_names = HashSet::new();
+
+        for door in &room.doors {
+            if !rooms.contains_key(&door.associated_room_name) {
+                issues.push(Issue::DanglingDoor {
+                    room: key.to_string(),
+                    door: door.name.clone(),
+                    target: door.associated_room_name.clone(),
+                });
+            }
+
+            if !door_names.insert(door.name.as_str()) {
+                issues.push(Issue::DuplicateDoor {
+                    room: key.to_string(),
+                    door: door.name.clone(),
+                });
+            }
+
+            if !door.locked {
+                continue;
+            }
+
+            let found = |lock: &Key| available_keys.iter().any(|k| k.fits(lock));
+
+            let missing = match &door.key {
+                None => {
+                    issues.push(Issue::LockedWithoutKey {
+                        room: key.to_string(),
+                        door: door.name.clone(),
+                    });
+                    continue;
+                }
+                Some(Lock::One(lock)) if !found(lock) => Some(Lock::One(lock.clone())),
+                Some(Lock::Any { any }) if !any.iter().any(found) => {
+                    Some(Lock::Any { any: any.clone() })
+                }
+                Some(Lock::All { all }) => Some(Lock::All {
+                    all: all.iter().filter(|k| !found(k)).cloned().collect(),
+                })
+                .filter(|lock| !lock.keys().is_empty()),
+                _ => None,
+            };
+
+            if let Some(lock) = missing {
+                issues.push(Issue::UnobtainableKey {
+                    room: key.to_string(),
+                    door: door.name.clone(),
+                    lock,
+                });
+            }
+        }
+    }
+
+    if rooms.contains_key(start_room) {
+        let reachable = reachable_rooms(rooms, start_room);
+
+        for key in &names {
+            if !reachable.contains(key.as_str()) {
+                issues.push(Issue::UnreachableRoom(key.to_string()));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Every room the player can walk to from `start`, ignoring locks and guards.
+fn reachable_rooms(rooms: &HashMap<String, Room>, start: &str) -> HashSet<String> {
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([start.to_string()]);
+
+    while let Some(name) = queue.pop_front() {
+        for door in exits(rooms, &name) {
+            if seen.insert(door.associated_room_name.clone()) {
+                queue.push_back(door.associated_room_name.clone());
+            }
+        }
+    }
+
+    seen
+}
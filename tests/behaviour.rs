@@ -0,0 +1,148 @@
+mod common;
+
+use gext::behaviour::MAX_HEALS;
+use gext::engine::{Engine, Event};
+use serde_json::{json, Value};
+
+fn item(name: &str, health: i32, attack: i32) -> Value {
+    json!({"name": name, "description": "", "health": health, "attack": attack, "defense": 0})
+}
+
+/// A warrior fights a guard that fights as `behaviour`, attacking every round and leaving any loot.
+fn battle(behaviour: &str, guard: Value) -> (Engine, Vec<Event>) {
+    let mut enemy = json!({"name": "Guard", "health": 150, "attack": 25, "defense": 10});
+    enemy["behaviour"] = json!(behaviour);
+
+    for (field, value) in guard.as_object().unwrap() {
+        enemy[field] = value.clone();
+    }
+
+    let map = common::guarded_map(&enemy.to_string());
+    let mut engine = common::engine(&map, common::class("Warrior"), 5);
+
+    let mut events = common::play(&mut engine, &["go Vault"]);
+    events.extend(common::fight(&mut engine, "attack"));
+
+    if engine.is_waiting() {
+        events.extend(common::play(&mut engine, &["leave"]));
+    }
+
+    (engine, events)
+}
+
+fn count(events: &[Event], matches: fn(&Event) -> bool) -> usize {
+    events.iter().filter(|e| matches(e)).count()
+}
+
+fn won(events: &[Event]) -> bool {
+    count(events, |e| matches!(e, Event::BattleWon(_))) == 1
+}
+
+fn used(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            Event::EnemyUsedItem(item) => Some(item.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn balanced_enemies_attack_every_round_and_use_any_item() {
+    let (_, events) = battle(
+        "balanced",
+        json!({"items": [item("potion", 10, 0), item("dagger", 0, 5)]}),
+    );
+
+    assert!(won(&events));
+    assert!(!used(&events).is_empty());
+    assert_eq!(
+        count(&events, |e| matches!(
+            e,
+            Event::EnemyDefended | Event::EnemyHealed(_) | Event::EnemyEnraged
+        )),
+        0
+    );
+}
+
+#[test]
+fn aggressive_enemies_only_use_items_that_help_them_attack() {
+    let (_, events) = battle(
+        "aggressive",
+        json!({"items": [item("potion", 50, 0), item("dagger", 0, 2), item("club", 0, 4)]}),
+    );
+
+    assert!(won(&events));
+    // the strongest first, and the potion never
+    assert_eq!(used(&events), ["club", "dagger"]);
+}
+
+#[test]
+fn defensive_enemies_guard_when_outmatched() {
+    let (_, events) = battle("defensive", json!({}));
+
+    assert!(won(&events));
+    assert!(count(&events, |e| matches!(e, Event::EnemyDefended)) > 0);
+
+    // a guard that hits as hard as the player never needs to
+    let (_, events) = battle("defensive", json!({"attack": 100, "health": 20}));
+
+    assert_eq!(count(&events, |e| matches!(e, Event::EnemyDefended)), 0);
+}
+
+#[test]
+fn healers_heal_below_half_health() {
+    let (_, events) = battle(
+        "healer",
+        json!({"attack": 25, "items": [item("potion", 30, 0), item("dagger", 0, 5)]}),
+    );
+
+    assert!(won(&events));
+    assert_eq!(used(&events), ["potion"]);
+
+    let heals = count(&events, |e| matches!(e, Event::EnemyHealed(_)));
+    assert!(heals > 0 && heals <= MAX_HEALS as usize, "{}", heals);
+}
+
+#[test]
+fn berserkers_enrage_once() {
+    let (_, events) = battle("berserker", json!({}));
+
+    assert!(won(&events));
+    assert_eq!(count(&events, |e| matches!(e, Event::EnemyEnraged)), 1);
+}
+
+#[test]
+fn fleeing_enemies_run_and_leave_the_door_open() {
+    let (engine, events) = battle("fleeing", json!({}));
+
+    assert_eq!(count(&events, |e| matches!(e, Event::EnemyFled(_))), 1);
+    assert!(!won(&events));
+    assert_eq!(engine.player().current_room, "Vault");
+    assert!(engine.player().battles[0].fled);
+}
+
+#[test]
+fn battles_replay_the_same_for_the_same_seed() {
+    for behaviour in [
+        "balanced",
+        "aggressive",
+        "defensive",
+        "healer",
+        "berserker",
+        "fleeing",
+    ] {
+        let guard = json!({"items": [item("potion", 10, 0), item("dagger", 0, 5)]});
+
+        let (_, first) = battle(behaviour, guard.clone());
+        let (_, second) = battle(behaviour, guard);
+
+        assert_eq!(
+            format!("{:?}", first),
+            format!("{:?}", second),
+            "{}",
+            behaviour
+        );
+    }
+}
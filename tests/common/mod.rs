@@ -0,0 +1,157 @@
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use gext::engine::{Command, Engine, Event, Prompt};
+use gext::map::{link_doors, Map};
+use gext::rng::GameRng;
+use gext::structs::{Class, Player};
+use serde_json::{json, Value};
+
+/// A hall with a door to a vault, guarded by `enemy` if it's given, as map JSON.
+pub fn guarded_map(enemy: &str) -> Map {
+    map(&format!(
+        r#"{{
+            "start_room": "Hall",
+            "lose_condition": null,
+            "rooms": {{
+                "Hall": {{
+                    "name": "Hall",
+                    "description": "a dusty hall",
+                    "doors": [{{
+                        "name": "Vault",
+                        "description": "a heavy door",
+                        "locked": false,
+                        "enemy": {},
+                        "associated_room_name": "Vault",
+                        "requirements": null
+                    }}],
+                    "items": [],
+                    "keys": []
+                }},
+                "Vault": {{"name": "Vault", "description": "gold everywhere", "doors": [], "items": [], "keys": []}}
+            }}
+        }}"#,
+        enemy
+    ))
+}
+
+/// An unlocked, unguarded door, as map JSON.
+pub fn door(name: &str, to: &str) -> Value {
+    json!({
+        "name": name,
+        "description": "a door",
+        "locked": false,
+        "enemy": null,
+        "associated_room_name": to,
+        "requirements": null
+    })
+}
+
+/// A room with nothing in it but `doors`, as map JSON.
+pub fn room(name: &str, doors: Vec<Value>) -> Value {
+    json!({"name": name, "description": "a room", "doors": doors, "items": [], "keys": []})
+}
+
+/// Parses a map document, linking its doors as loading a map file does.
+pub fn map(json: &str) -> Map {
+    let mut map: Map = serde_json::from_str(json).unwrap();
+    link_doors(&mut map.rooms);
+    map
+}
+
+pub fn class(name: &str) -> Class {
+    Class::builtins()
+        .into_iter()
+        .find(|c| c.name == name)
+        .unwrap()
+}
+
+/// A new player on `map`, with its rolls seeded by `seed`.
+pub fn player(map: &Map, class: Class, seed: u64) -> Player {
+    let mut player = Player::init(
+        "Tester".to_string(),
+        map.rooms.clone(),
+        "test".to_string(),
+        map.start_room.clone(),
+        class,
+    );
+    player.rng = Some(GameRng::new(seed));
+    player
+}
+
+/// An engine for a new game on `map`, set up as the client sets one up.
+pub fn engine(map: &Map, class: Class, seed: u64) -> Engine {
+    Engine::new(player(map, class, seed))
+        .with_rules(map.rules)
+        .with_map(map.rooms.clone())
+        .with_conditions(map.win_condition.clone(), map.lose_condition.clone())
+}
+
+/// Runs each command in turn, answering with a [`Command::Answer`] whenever the engine is
+/// waiting on one, and returns every event.
+pub fn play(engine: &mut Engine, commands: &[&str]) -> Vec<Event> {
+    let mut events = vec![];
+
+    for command in commands {
+        let command = if engine.is_waiting() {
+            Command::Answer(command.to_string())
+        } else {
+            parse(command)
+        };
+
+        events.extend(engine.handle(command));
+    }
+
+    events
+}
+
+/// Answers every round of the battle under way with `action`, until it's over. Stops at the loot
+/// the enemy drops, if there's any.
+pub fn fight(engine: &mut Engine, action: &str) -> Vec<Event> {
+    let mut events: Vec<Event> = vec![];
+
+    for _ in 0..100 {
+        let looting = matches!(events.last(), Some(Event::Prompt(Prompt::Loot(_))));
+
+        if !engine.is_waiting() || looting {
+            return events;
+        }
+
+        events.extend(engine.handle(Command::Answer(action.to_string())));
+    }
+
+    panic!("the battle went on for 100 rounds");
+}
+
+fn parse(command: &str) -> Command {
+    let (verb, rest) = command.split_once(' ').unwrap_or((command, ""));
+    let rest = rest.to_string();
+
+    match verb {
+        "look" => Command::Look,
+        "go" => Command::Go(rest),
+        "take" => Command::Take(rest),
+        "takekey" => Command::TakeKey(rest),
+        "use" => Command::Use(rest),
+        "unlock" => Command::Unlock(rest),
+        "lock" => Command::Lock(rest),
+        "search" => Command::Search,
+        "battles" => Command::Battles,
+        "inventory" => Command::Inventory,
+        "stats" => Command::Stats,
+        "save" => Command::Save,
+        "load" => Command::Load(rest),
+        _ => panic!("unknown command {:?}", command),
+    }
+}
+
+/// An empty directory of its own for a test to write to.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
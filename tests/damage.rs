@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use gext::damage::{percent_of, Rules};
+use gext::rng::GameRng;
+use gext::structs::{Class, Item, Player};
+use proptest::prelude::*;
+
+fn rules() -> impl Strategy<Value = Rules> {
+    (0u32..=200, 0u32..=50, 0u32..=150, 0u32..=500).prop_map(
+        |(variance, min_damage, crit_chance, crit_multiplier)| Rules {
+            variance,
+            min_damage,
+            crit_chance,
+            crit_multiplier,
+        },
+    )
+}
+
+fn crit() -> impl Strategy<Value = Option<(u32, u32)>> {
+    proptest::option::of((0u32..=100, 0u32..=500))
+}
+
+proptest! {
+    #[test]
+    fn rolls_stay_within_the_spread(rules in rules(), attack in -1000i32..100_000, seed: u64) {
+        let rolled = rules.roll(&mut GameRng::new(seed), attack);
+        let spread = rules.spread(attack);
+
+        prop_assert!(spread >= 0);
+        prop_assert!((attack - spread..=attack + spread).contains(&rolled));
+    }
+
+    #[test]
+    fn rolls_never_panic(rules in rules(), attack: i32, seed: u64) {
+        rules.roll(&mut GameRng::new(seed), attack);
+    }
+
+    #[test]
+    fn damage_is_never_below_the_minimum(
+        rules in rules(),
+        attack: i32,
+        defense: i32,
+        percent: u32,
+        multiplier: Option<u32>,
+    ) {
+        let damage = rules.damage(attack, defense, percent, multiplier);
+
+        prop_assert!(damage >= 0);
+        prop_assert!(damage >= rules.min_damage as i32);
+    }
+
+    #[test]
+    fn more_defense_never_means_more_damage(
+        rules in rules(),
+        attack in -10_000i32..10_000,
+        defense in -10_000i32..10_000,
+        extra in 0i32..10_000,
+        percent in 0u32..=500,
+    ) {
+        prop_assert!(
+            rules.damage(attack, defense + extra, percent, None)
+                <= rules.damage(attack, defense, percent, None)
+        );
+    }
+
+    #[test]
+    fn more_attack_never_means_less_damage(
+        rules in rules(),
+        attack in -10_000i32..10_000,
+        defense in -10_000i32..10_000,
+        extra in 0i32..10_000,
+        percent in 0u32..=500,
+    ) {
+        prop_assert!(
+            rules.damage(attack + extra, defense, percent, None)
+                >= rules.damage(attack, defense, percent, None)
+        );
+    }
+
+    #[test]
+    fn hits_are_plain_or_critical(
+        rules in rules(),
+        attack in -10_000i32..10_000,
+        defense in -10_000i32..10_000,
+        percent in 0u32..=500,
+        crit in crit(),
+        seed: u64,
+    ) {
+        let hit = rules.hit(&mut GameRng::new(seed), attack, defense, percent, crit);
+
+        let expected = match rules.crit(crit) {
+            Some((_, multiplier)) if hit.critical => {
+                rules.damage(attack, defense, percent, Some(multiplier))
+            }
+            _ => {
+                prop_assert!(!hit.critical);
+                rules.damage(attack, defense, percent, None)
+            }
+        };
+
+        prop_assert_eq!(hit.damage, expected);
+    }
+
+    #[test]
+    fn crits_without_a_chance_never_land(
+        rules in rules(),
+        attack in -10_000i32..10_000,
+        defense in -10_000i32..10_000,
+        seed: u64,
+    ) {
+        let rules = Rules { crit_chance: 0, ..rules };
+        let hit = rules.hit(&mut GameRng::new(seed), attack, defense, 100, None);
+
+        prop_assert!(!hit.critical);
+    }
+
+    #[test]
+    fn average_damage_is_between_plain_and_critical(
+        rules in rules(),
+        attack in -10_000i32..10_000,
+        defense in -10_000i32..10_000,
+        percent in 0u32..=500,
+        crit in crit(),
+    ) {
+        let average = rules.average_damage(attack, defense, percent, crit);
+        let plain = rules.damage(attack, defense, percent, None) as f64;
+        let critical = match rules.crit(crit) {
+            Some((_, multiplier)) => rules.damage(attack, defense, percent, Some(multiplier)) as f64,
+            None => plain,
+        };
+
+        prop_assert!(average >= plain.min(critical) - 1e-9);
+        prop_assert!(average <= plain.max(critical) + 1e-9);
+    }
+
+    #[test]
+    fn percentages_stop_at_the_ends_of_i32(value: i32, percent: i64) {
+        let exact = value as i128 * percent as i128 / 100;
+
+        prop_assert_eq!(
+            percent_of(value, percent) as i128,
+            exact.clamp(i32::MIN as i128, i32::MAX as i128)
+        );
+    }
+
+    #[test]
+    fn items_buff_all_three_stats(
+        health: i32,
+        attack: i32,
+        defense: i32,
+    ) {
+        let mut player = Player::init(
+            "Tester".to_string(),
+            HashMap::new(),
+            "test".to_string(),
+            "Entrance Hall".to_string(),
+            Class::default(),
+        );
+        let before = player.stats();
+        let item = Item::new("potion".to_string(), String::new(), health, attack, defense);
+
+        player.items_held.push(item.clone());
+        player.use_item(item);
+
+        prop_assert_eq!(player.stats().health, before.health.saturating_add(health));
+        prop_assert_eq!(player.stats().attack, before.attack.saturating_add(attack));
+        prop_assert_eq!(player.stats().defense, before.defense.saturating_add(defense));
+        prop_assert!(player.items_held.is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use gext::map::LoseCondition;
+use gext::structs::Key;
+use serde_json::json;
+
+#[test]
+fn only_moves_take_a_turn() {
+    let map = common::guarded_map("null");
+    let mut engine = common::engine(&map, common::class("Warrior"), 1);
+
+    common::play(
+        &mut engine,
+        &[
+            "look",
+            "search",
+            "inventory",
+            "stats",
+            "battles",
+            "go Nowhere",
+            "take nothing",
+            "unlock Vault",
+        ],
+    );
+
+    assert_eq!(engine.player().turn, 0);
+
+    common::play(&mut engine, &["go Vault"]);
+
+    assert_eq!(engine.player().turn, 1);
+}
+
+#[test]
+fn looking_around_never_runs_out_the_clock() {
+    let map = common::guarded_map("null");
+    let mut engine = common::engine(&map, common::class("Warrior"), 1)
+        .with_conditions(None, Some(LoseCondition::TurnLimit { turns: 1 }));
+
+    common::play(&mut engine, &["inventory", "stats", "look", "inventory"]);
+
+    assert!(!engine.is_over());
+
+    common::play(&mut engine, &["go Vault"]);
+
+    assert!(engine.is_over());
+}
+
+#[test]
+fn a_single_use_key_is_kept_when_the_door_turns_the_player_away() {
+    let mut door = common::door("Vault", "Vault");
+    door["locked"] = json!(true);
+    door["key"] = json!({"name": "k", "single_use": true});
+    door["requirements"] = json!({"health": 1000, "attack": 0, "defense": 0});
+
+    let map = common::map(
+        &json!({
+            "start_room": "Hall",
+            "lose_condition": null,
+            "rooms": {"Hall": common::room("Hall", vec![door]), "Vault": common::room("Vault", vec![])}
+        })
+        .to_string(),
+    );
+
+    let mut engine = common::engine(&map, common::class("Warrior"), 1);
+    let holds_k =
+        |engine: &gext::engine::Engine| engine.player().keys_held.iter().any(|k| k.name == "k");
+
+    engine.player_mut().keys_held.push(Key {
+        name: "k".to_string(),
+        single_use: true,
+        opens: vec![],
+    });
+
+    let events = format!("{:?}", common::play(&mut engine, &["go Vault"]));
+
+    assert!(events.contains("RequirementsNotMet"), "{}", events);
+    assert!(holds_k(&engine));
+    assert!(engine.player().room().doors[0].locked);
+
+    engine.player_mut().health = 1000;
+    common::play(&mut engine, &["go Vault"]);
+
+    assert_eq!(engine.player().current_room, "Vault");
+    assert!(!holds_k(&engine));
+}
@@ -0,0 +1,96 @@
+use gext::structs::{Key, Lock};
+
+fn key(name: &str) -> Key {
+    Key::new(name.to_string())
+}
+
+fn single_use(name: &str) -> Key {
+    Key {
+        single_use: true,
+        ..key(name)
+    }
+}
+
+fn master(name: &str, opens: &[&str]) -> Key {
+    Key {
+        opens: opens.iter().map(|name| name.to_string()).collect(),
+        ..key(name)
+    }
+}
+
+fn all(names: &[&str]) -> Lock {
+    Lock::All {
+        all: names.iter().map(|name| key(name)).collect(),
+    }
+}
+
+fn any(names: &[&str]) -> Lock {
+    Lock::Any {
+        any: names.iter().map(|name| key(name)).collect(),
+    }
+}
+
+#[test]
+fn one_key_locks_take_that_key() {
+    let lock = Lock::One(key("kitchen"));
+
+    assert_eq!(lock.find(&[key("pantry"), key("kitchen")]), Some(vec![1]));
+    assert_eq!(lock.find(&[key("pantry")]), None);
+    assert_eq!(lock.find(&[]), None);
+}
+
+#[test]
+fn all_locks_need_every_key() {
+    let lock = all(&["red", "blue"]);
+
+    assert_eq!(lock.find(&[key("red")]), None);
+    assert_eq!(lock.find(&[key("blue"), key("green")]), None);
+    assert_eq!(
+        lock.find(&[key("blue"), key("green"), key("red")]),
+        Some(vec![2, 0])
+    );
+}
+
+#[test]
+fn any_locks_take_a_key_that_lasts_over_one_that_doesnt() {
+    let lock = any(&["red", "blue"]);
+
+    assert_eq!(lock.find(&[single_use("red"), key("blue")]), Some(vec![1]));
+    assert_eq!(lock.find(&[single_use("red"), key("green")]), Some(vec![0]));
+    assert_eq!(lock.find(&[key("green")]), None);
+}
+
+#[test]
+fn a_key_made_for_the_lock_beats_a_master_key() {
+    let lock = Lock::One(key("kitchen"));
+
+    assert_eq!(
+        lock.find(&[master("master", &["kitchen"]), key("kitchen")]),
+        Some(vec![1])
+    );
+
+    // but a master key that lasts beats a single-use key
+    assert_eq!(
+        lock.find(&[single_use("kitchen"), master("master", &["kitchen"])]),
+        Some(vec![1])
+    );
+}
+
+#[test]
+fn master_keys_open_what_they_list() {
+    let skeleton = [master("skeleton", &["red", "blue"])];
+
+    assert_eq!(Lock::One(key("red")).find(&skeleton), Some(vec![0]));
+    assert_eq!(Lock::One(key("green")).find(&skeleton), None);
+
+    // one master key can stand in for every key of an all lock it opens
+    assert_eq!(all(&["red", "blue"]).find(&skeleton), Some(vec![0]));
+    assert_eq!(all(&["red", "green"]).find(&skeleton), None);
+}
+
+#[test]
+fn blank_keys_mean_no_key() {
+    assert_eq!(Lock::One(key("")).without_blanks(), None);
+    assert_eq!(any(&["", ""]).without_blanks(), None);
+    assert_eq!(all(&["red", ""]).without_blanks(), Some(all(&["red"])));
+}
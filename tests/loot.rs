@@ -0,0 +1,184 @@
+mod common;
+
+use gext::engine::{Engine, EngineError, Event, Prompt};
+use gext::structs::Pickup;
+use serde_json::{json, Value};
+
+fn item(name: &str, health: i32, attack: i32) -> Value {
+    json!({"name": name, "description": "", "health": health, "attack": attack, "defense": 0})
+}
+
+/// A warrior beats a guard dropping a gem, a ring and the vault key, and is offered the loot.
+fn beat(seed: u64, guard: Value) -> (Engine, Vec<Event>) {
+    let mut enemy = json!({
+        "name": "Guard",
+        "health": 50,
+        "attack": 5,
+        "defense": 0,
+        "loot": {"items": [item("gem", 0, 0), item("ring", 0, 0)], "keys": [{"name": "vault"}]}
+    });
+
+    for (field, value) in guard.as_object().unwrap() {
+        enemy[field] = value.clone();
+    }
+
+    let map = common::guarded_map(&enemy.to_string());
+    let mut engine = common::engine(&map, common::class("Warrior"), seed);
+
+    let mut events = common::play(&mut engine, &["go Vault"]);
+    events.extend(common::fight(&mut engine, "attack"));
+
+    (engine, events)
+}
+
+fn held(engine: &Engine) -> (Vec<String>, Vec<String>) {
+    let player = engine.player();
+
+    (
+        player.items_held.iter().map(|i| i.name.clone()).collect(),
+        player.keys_held.iter().map(|k| k.name.clone()).collect(),
+    )
+}
+
+fn lying_in_vault(engine: &Engine) -> (Vec<String>, Vec<String>) {
+    let vault = &engine.player().map["Vault"];
+
+    (
+        vault.items.iter().map(|i| i.name.clone()).collect(),
+        vault.keys.iter().map(|k| k.name.clone()).collect(),
+    )
+}
+
+#[test]
+fn the_enemy_s_own_items_are_offered_with_its_loot() {
+    // aggressive enemies never drink potions, so it still has it to drop
+    let (engine, events) = beat(
+        1,
+        json!({"behaviour": "aggressive", "items": [item("potion", 10, 0)]}),
+    );
+
+    let Some(Event::Prompt(Prompt::Loot(loot))) = events.last() else {
+        panic!("no loot was offered: {:?}", events.last());
+    };
+
+    let names: Vec<&str> = loot.iter().map(Pickup::name).collect();
+
+    assert_eq!(names, ["potion", "gem", "ring", "vault"]);
+    assert!(engine.is_waiting());
+}
+
+#[test]
+fn taking_all_the_loot() {
+    let (mut engine, _) = beat(1, json!({}));
+    let (items, keys) = held(&engine);
+
+    let events = common::play(&mut engine, &["all"]);
+
+    assert!(!engine.is_waiting());
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| matches!(e, Event::ItemTaken(_) | Event::KeyTaken(_)))
+            .count(),
+        3
+    );
+    assert_eq!(
+        held(&engine).0,
+        [items, vec!["gem".into(), "ring".into()]].concat()
+    );
+    assert_eq!(held(&engine).1, [keys, vec!["vault".into()]].concat());
+    assert_eq!(lying_in_vault(&engine), (vec![], vec![]));
+}
+
+#[test]
+fn leaving_the_loot_drops_it_in_the_room() {
+    let (mut engine, _) = beat(1, json!({}));
+    let before = held(&engine);
+
+    let events = common::play(&mut engine, &["leave"]);
+
+    assert!(!engine.is_waiting());
+    assert!(matches!(events.last(), Some(Event::LootLeft(left)) if left.len() == 3));
+    assert_eq!(held(&engine), before);
+    assert_eq!(
+        lying_in_vault(&engine),
+        (vec!["gem".into(), "ring".into()], vec!["vault".into()])
+    );
+
+    // and it can still be picked up later, like anything else in a room
+    let events = common::play(&mut engine, &["take gem"]);
+    let Some(Event::Prompt(Prompt::Riddle(riddle))) = events.last() else {
+        panic!("taking the gem didn't ask a riddle: {:?}", events.last());
+    };
+    common::play(&mut engine, &[riddle.answers[0].as_str()]);
+
+    assert!(held(&engine).0.contains(&"gem".to_string()));
+}
+
+#[test]
+fn loot_can_be_taken_one_at_a_time() {
+    let (mut engine, _) = beat(1, json!({}));
+
+    let events = common::play(&mut engine, &["gem", "sword"]);
+
+    assert!(held(&engine).0.contains(&"gem".to_string()));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::Error(EngineError::NoSuchItem))));
+
+    // the rest is still on offer
+    let events = common::play(&mut engine, &["leave"]);
+
+    assert!(matches!(events.last(), Some(Event::LootLeft(left)) if left.len() == 2));
+}
+
+#[test]
+fn beaten_enemies_are_looted_once() {
+    let (mut engine, _) = beat(1, json!({}));
+    common::play(&mut engine, &["leave"]);
+
+    let guard = engine.player().map["Hall"].doors[0].enemy.clone().unwrap();
+
+    assert!(guard.items.is_empty());
+    assert!(guard.loot.items.is_empty() && guard.loot.keys.is_empty());
+}
+
+#[test]
+fn winning_gains_the_enemy_s_stats_from_before_the_battle() {
+    // a guard that drinks a strength potion part way through
+    let guard = json!({
+        "health": 150,
+        "attack": 25,
+        "defense": 10,
+        "behaviour": "balanced",
+        "items": [item("strength", 0, 10)]
+    });
+
+    let map = common::guarded_map("null");
+    let before = common::engine(&map, common::class("Warrior"), 5)
+        .player()
+        .stats();
+
+    let (engine, events) = beat(5, guard);
+
+    assert!(events.iter().any(|e| matches!(e, Event::BattleWon(_))));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::EnemyUsedItem(item) if item.name == "strength")));
+
+    let class = &engine.player().class;
+    let after = engine.player().stats();
+
+    assert_eq!(
+        after.health,
+        before.health + 150 + class.won_battle_health_bonus
+    );
+    assert_eq!(
+        after.attack,
+        before.attack + 25 + class.won_battle_attack_bonus
+    );
+    assert_eq!(
+        after.defense,
+        before.defense + 10 + class.won_battle_defense_bonus
+    );
+}
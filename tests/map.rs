@@ -0,0 +1,201 @@
+mod common;
+
+use std::path::PathBuf;
+
+use gext::content::load_map;
+use gext::map::{link_doors, Map, START_ROOM};
+use gext::structs::{Key, Lock};
+use serde_json::{json, Value};
+
+fn write(dir: &str, map: &Value) -> PathBuf {
+    let path = common::temp_dir(dir).join("test.map.json");
+    std::fs::write(&path, map.to_string()).unwrap();
+    path
+}
+
+fn load(dir: &str, map: &Value) -> Map {
+    load_map(write(dir, map).to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn maps_with_a_header_load() {
+    let map = load(
+        "load-document",
+        &json!({
+            "title": "Test",
+            "start_room": "Hall",
+            "rooms": {
+                "Hall": common::room("Hall", vec![common::door("Vault", "Vault")]),
+                "Vault": common::room("Vault", vec![])
+            }
+        }),
+    );
+
+    assert_eq!(map.title, "Test");
+    assert_eq!(map.start_room, "Hall");
+    assert_eq!(map.rooms.len(), 2);
+}
+
+#[test]
+fn maps_that_are_just_rooms_load() {
+    let map = load(
+        "load-legacy",
+        &json!({
+            START_ROOM: common::room(START_ROOM, vec![common::door("Vault", "Vault")]),
+            "Vault": common::room("Vault", vec![])
+        }),
+    );
+
+    assert_eq!(map.title, "");
+    assert_eq!(map.start_room, START_ROOM);
+    assert_eq!(map.rooms.len(), 2);
+}
+
+#[test]
+fn rooms_can_be_called_rooms_or_doors() {
+    for name in ["rooms", "doors", "start_room"] {
+        let map = load(
+            &format!("load-legacy-{}", name),
+            &json!({
+                START_ROOM: common::room(START_ROOM, vec![common::door(name, name)]),
+                name: common::room(name, vec![])
+            }),
+        );
+
+        assert_eq!(map.start_room, START_ROOM);
+        assert!(
+            map.rooms.contains_key(name),
+            "the {:?} room is missing",
+            name
+        );
+    }
+}
+
+#[test]
+fn broken_maps_are_reported_as_maps() {
+    let path = write(
+        "load-broken",
+        &json!({
+            "start_room": 3,
+            "rooms": {"Hall": common::room("Hall", vec![])}
+        }),
+    );
+
+    let error = load_map(path.to_str().unwrap()).unwrap_err().to_string();
+
+    assert!(error.contains("expected a string"), "{}", error);
+}
+
+/// A hall with `door` out of it to a vault, linked up.
+fn linked(door: Value) -> Map {
+    common::map(
+        &json!({
+            "start_room": "Hall",
+            "rooms": {
+                "Hall": common::room("Hall", vec![door]),
+                "Vault": common::room("Vault", vec![])
+            }
+        })
+        .to_string(),
+    )
+}
+
+#[test]
+fn two_way_doors_get_a_way_back() {
+    let map = linked(common::door("Vault", "Vault"));
+    let vault = &map.rooms["Vault"];
+
+    assert_eq!(vault.doors.len(), 1);
+
+    let back = &vault.doors[0];
+
+    assert_eq!(back.name, "Hall");
+    assert_eq!(back.associated_room_name, "Hall");
+    assert_eq!(back.description, "a door to the Hall");
+    assert!(!back.locked);
+    assert!(back.key.is_none() && back.enemy.is_none() && back.requirements.is_none());
+}
+
+#[test]
+fn one_way_doors_dont() {
+    let mut door = common::door("Vault", "Vault");
+    door["one_way"] = json!(true);
+
+    assert!(linked(door).rooms["Vault"].doors.is_empty());
+}
+
+#[test]
+fn the_way_back_can_be_locked_and_named() {
+    let mut door = common::door("Vault", "Vault");
+    door["back"] = json!({"name": "Gate", "locked": true, "key": {"name": "gate key"}});
+
+    let map = linked(door);
+    let back = &map.rooms["Vault"].doors[0];
+
+    assert_eq!(back.name, "Gate");
+    assert_eq!(back.associated_room_name, "Hall");
+    assert!(back.locked);
+    assert_eq!(
+        back.key,
+        Some(Lock::One(Key {
+            name: "gate key".to_string(),
+            ..Key::default()
+        }))
+    );
+}
+
+#[test]
+fn a_room_s_own_way_back_is_kept() {
+    let mut map = common::map(
+        &json!({
+            "start_room": "Hall",
+            "rooms": {
+                "Hall": common::room("Hall", vec![common::door("Vault", "Vault")]),
+                "Vault": common::room("Vault", vec![common::door("Trapdoor", "Hall")])
+            }
+        })
+        .to_string(),
+    );
+
+    let names = |map: &Map| -> Vec<String> {
+        map.rooms["Vault"]
+            .doors
+            .iter()
+            .map(|d| d.name.clone())
+            .collect()
+    };
+
+    assert_eq!(names(&map), ["Trapdoor"]);
+
+    // and linking again changes nothing
+    let before = serde_json::to_value(&map.rooms).unwrap();
+    link_doors(&mut map.rooms);
+
+    assert_eq!(serde_json::to_value(&map.rooms).unwrap(), before);
+    assert_eq!(map.rooms["Hall"].doors.len(), 1);
+}
+
+#[test]
+fn old_player_shaped_enemies_still_load() {
+    // enemies used to be whole players, down to a class, a map and a current room
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/player_enemy.map.json");
+    let map = load_map(path.to_str().unwrap()).unwrap();
+
+    let door = &map.rooms["Backstage"].doors[0];
+    let enemy = door.enemy.as_ref().unwrap();
+
+    assert_eq!(enemy.name, "Stressed actor");
+    assert_eq!((enemy.health, enemy.attack, enemy.defense), (100, 20, 10));
+
+    let items: Vec<&str> = enemy.items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(items, ["costume", "makeup"]);
+    assert_eq!(enemy.keys, [Key::new("dressing room".to_string())]);
+    assert!(enemy.loot.is_empty());
+
+    // and they're saved in the new shape
+    let saved = serde_json::to_value(enemy).unwrap();
+
+    assert_eq!(saved["items"].as_array().unwrap().len(), 2);
+    assert!(saved.get("items_held").is_none() && saved.get("class").is_none());
+}
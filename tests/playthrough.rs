@@ -0,0 +1,73 @@
+use gext::client::{self, Options};
+use gext::content::Content;
+use gext::io::{ScriptedIo, TranscriptIo, TranscriptLine};
+use gext::map::Map;
+
+fn map() -> Map {
+    serde_json::from_str(
+        r#"{
+            "start_room": "Hall",
+            "win_condition": {"type": "reach_room", "room": "Vault"},
+            "rooms": {
+                "Hall": {
+                    "name": "Hall",
+                    "description": "a dusty hall",
+                    "doors": [{"name": "Vault", "description": "a heavy door", "locked": false, "associated_room_name": "Vault", "enemy": null, "requirements": null}],
+                    "items": [],
+                    "keys": []
+                },
+                "Vault": {"name": "Vault", "description": "gold everywhere", "doors": [], "items": [], "keys": []}
+            }
+        }"#,
+    )
+    .unwrap()
+}
+
+fn play(input: &[&str]) -> TranscriptIo<ScriptedIo> {
+    let mut io = TranscriptIo::new(ScriptedIo::new(input.iter().copied()));
+    let content = Content {
+        map: map(),
+        ..Content::default()
+    };
+    let options = Options {
+        no_save: true,
+        seed: Some(1),
+        ..Options::default()
+    };
+
+    client::play(&mut io, content, &options);
+
+    io
+}
+
+#[test]
+fn walking_into_the_goal_wins() {
+    let io = play(&["n", "Warrior", "Tester", "test", "look", "go Vault"]);
+    let text = io.to_text();
+
+    assert_eq!(
+        io.transcript()[..2],
+        [
+            TranscriptLine::Output {
+                text: "Would you like to load a savegame? (y/n)".to_string(),
+                color: "yellow".to_string(),
+            },
+            TranscriptLine::Input("n".to_string()),
+        ]
+    );
+    assert!(text.contains("Welcome to the game, Tester! You are in the Hall\n"));
+    assert!(text.contains("> look\na dusty hall\n"));
+    assert!(text.contains("> go Vault\nYou are in the Vault\nYou won! Congratulations!\n"));
+}
+
+#[test]
+fn running_out_of_input_stops_the_game() {
+    let io = play(&["n", "Warrior", "Tester", "test", "look"]);
+    let text = io.to_text();
+
+    assert!(text.ends_with("> look\na dusty hall\n"));
+    assert!(io
+        .into_inner()
+        .lines()
+        .contains(&"Welcome to the game, Tester! You are in the Hall"));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use gext::map::Map;
+use gext::riddles::{RiddlePack, WrongAnswerPolicy};
+use gext::structs::Class;
+use gext::validate::{validate_map, Issue};
+
+fn hall(header: &str) -> Map {
+    common::map(&format!(
+        r#"{{
+            {}
+            "start_room": "Hall",
+            "rooms": {{
+                "Hall": {{"name": "Hall", "description": "a dusty hall", "doors": [], "items": [], "keys": []}}
+            }}
+        }}"#,
+        header
+    ))
+}
+
+#[test]
+fn a_map_without_a_policy_uses_the_pack_s() {
+    let mut pack = RiddlePack::builtin();
+    pack.on_wrong_answer.attempts = 2;
+
+    assert_eq!(hall("").wrong_answer_policy(&pack), &pack.on_wrong_answer);
+}
+
+#[test]
+fn a_map_s_policy_replaces_the_pack_s() {
+    let map = hall(r#""wrong_answer": {"attempts": 3, "despawn_keys": false},"#);
+
+    let mut pack = RiddlePack::builtin();
+    pack.on_wrong_answer.health_cost = 10;
+
+    assert_eq!(
+        map.wrong_answer_policy(&pack),
+        &WrongAnswerPolicy {
+            attempts: 3,
+            despawn_keys: false,
+            ..WrongAnswerPolicy::default()
+        }
+    );
+}
+
+#[test]
+fn a_map_s_policy_is_validated() {
+    let map = hall(r#""wrong_answer": {"attempts": 0, "health_cost": -5},"#);
+
+    let rules: Vec<&str> = validate_map(&map, &Class::builtins())
+        .into_iter()
+        .filter_map(|issue| match issue {
+            Issue::BadRule { rule, .. } => Some(rule),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rules, ["wrong_answer.attempts", "wrong_answer.health_cost"]);
+}
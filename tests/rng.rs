@@ -0,0 +1,69 @@
+mod common;
+
+use gext::engine::Engine;
+use gext::rng::GameRng;
+use gext::save::SaveManager;
+use rand::Rng;
+
+const GUARD: &str = r#"{"name": "Guard", "health": 60, "attack": 30, "defense": 10}"#;
+
+/// Goes for the vault and fights the guard to the end.
+fn fight(engine: &mut Engine) -> String {
+    let mut events = common::play(engine, &["go Vault"]);
+    events.extend(common::fight(engine, "attack"));
+
+    format!("{:?}", events)
+}
+
+fn rolls(rng: &mut GameRng) -> Vec<u32> {
+    (0..20).map(|_| rng.gen_range(0..1000)).collect()
+}
+
+#[test]
+fn the_same_seed_gives_the_same_rolls() {
+    assert_eq!(rolls(&mut GameRng::new(7)), rolls(&mut GameRng::new(7)));
+    assert_ne!(rolls(&mut GameRng::new(7)), rolls(&mut GameRng::new(8)));
+}
+
+#[test]
+fn a_saved_rng_carries_on_where_it_stopped() {
+    let mut rng = GameRng::new(7);
+    rolls(&mut rng);
+
+    let json = serde_json::to_string(&rng).unwrap();
+    let mut loaded: GameRng = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.seed(), 7);
+    assert_eq!(rolls(&mut loaded), rolls(&mut rng));
+}
+
+#[test]
+fn the_same_seed_gives_the_same_game() {
+    let map = common::guarded_map(GUARD);
+
+    let first = fight(&mut common::engine(&map, common::class("Warrior"), 3));
+    let second = fight(&mut common::engine(&map, common::class("Warrior"), 3));
+
+    assert!(first.contains("BattleWon"));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn a_loaded_game_rolls_as_the_saved_one_would_have() {
+    let map = common::guarded_map(GUARD);
+    let saves = SaveManager::new(common::temp_dir("rng-save"));
+
+    let mut engine = common::engine(&map, common::class("Warrior"), 3).with_saves(saves.clone());
+    common::play(&mut engine, &["look", "save"]);
+
+    let carried_on = fight(&mut engine);
+
+    let player = saves.load("test").unwrap().restore(&map.rooms).unwrap();
+    let mut loaded = Engine::new(player)
+        .with_rules(map.rules)
+        .with_map(map.rooms.clone())
+        .with_conditions(map.win_condition.clone(), map.lose_condition.clone());
+
+    assert_eq!(loaded.seed(), 3);
+    assert_eq!(fight(&mut loaded), carried_on);
+}
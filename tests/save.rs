@@ -0,0 +1,220 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use gext::engine::Engine;
+use gext::map::Map;
+use gext::save::{backup_name, fingerprint, migrate, SaveFile, SaveManager, FORMAT_VERSION};
+use gext::structs::Room;
+use serde_json::Value;
+
+/// A save from before saves had a format version: just the player, with the whole map inside.
+fn v0_save() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("gext.save.json")
+}
+
+#[test]
+fn the_fixture_is_a_bare_player() {
+    let value: Value = serde_json::from_reader(File::open(v0_save()).unwrap()).unwrap();
+
+    assert!(value.get("format_version").is_none());
+    assert!(value["current_room"].is_object());
+}
+
+#[test]
+fn v0_saves_migrate_to_the_current_format() {
+    let value: Value = serde_json::from_reader(File::open(v0_save()).unwrap()).unwrap();
+    let migrated = migrate(value).unwrap();
+
+    assert_eq!(migrated["format_version"], FORMAT_VERSION);
+    assert_eq!(migrated["game_version"], "unknown");
+    assert!(migrated["map_fingerprint"].is_null());
+    assert_eq!(migrated["player"]["current_room"], "Armory");
+    assert!(migrated["player"]["map"].is_object());
+}
+
+#[test]
+fn v0_saves_restore() {
+    let save = SaveFile::load(&v0_save()).unwrap();
+
+    assert_eq!(save.format_version, FORMAT_VERSION);
+
+    let player = save.restore(&Map::builtin().rooms).unwrap();
+
+    assert_eq!(player.name, "n");
+    assert_eq!(player.class.name, "Warrior");
+    assert_eq!(player.current_room, "Armory");
+    assert_eq!(player.battles.len(), 2);
+    assert!(player.battles.iter().all(|b| !b.winner));
+    assert_eq!(player.room().name, "Armory");
+    assert_eq!(player.ending, None);
+
+    // the dungeon doors in the old built-in map led to a room that was never there
+    for room in player.map.values() {
+        for door in &room.doors {
+            assert!(player.map.contains_key(&door.associated_room_name));
+        }
+    }
+
+    let map = Map::builtin();
+    let mut engine = Engine::new(player)
+        .with_map(map.rooms.clone())
+        .with_conditions(map.win_condition, map.lose_condition);
+
+    common::play(&mut engine, &["look", "go Entrance Hall"]);
+
+    assert_eq!(engine.player().current_room, "Entrance Hall");
+    assert_eq!(engine.player().ending, None);
+}
+
+#[test]
+fn fingerprints_ignore_the_order_rooms_and_doors_are_stored_in() {
+    let rooms = Map::builtin().rooms;
+
+    let mut shuffled: HashMap<String, Room> = HashMap::with_capacity(100);
+    let mut names: Vec<&String> = rooms.keys().collect();
+    names.sort();
+
+    for name in names.into_iter().rev() {
+        let mut room = rooms[name].clone();
+        room.doors.reverse();
+        shuffled.insert(name.clone(), room);
+    }
+
+    assert_eq!(fingerprint(&shuffled), fingerprint(&rooms));
+
+    shuffled.get_mut("Armory").unwrap().description.push('!');
+
+    assert_ne!(fingerprint(&shuffled), fingerprint(&rooms));
+}
+
+#[test]
+fn v0_saves_play_on_without_their_old_losses() {
+    let map = Map::builtin();
+    let player = SaveFile::load(&v0_save())
+        .unwrap()
+        .restore(&map.rooms)
+        .unwrap();
+
+    let mut engine = Engine::new(player)
+        .with_map(map.rooms.clone())
+        .with_conditions(map.win_condition.clone(), map.lose_condition.clone());
+
+    common::play(&mut engine, &["look"]);
+
+    assert!(!engine.is_over());
+    assert_eq!(engine.player().battles_before_endings, 2);
+
+    // a battle lost from here on still counts
+    let lost = engine.player().battles[0].clone();
+    engine.player_mut().battles.push(lost);
+    common::play(&mut engine, &["look"]);
+
+    assert!(engine.is_over());
+}
+
+/// A save of a new game on `map`, with `health` to tell saves apart.
+fn save_with(map: &Map, health: i32) -> SaveFile {
+    let mut player = common::player(map, common::class("Warrior"), 1);
+    player.health = health;
+
+    SaveFile::new(&player, Some(&map.rooms))
+}
+
+#[test]
+fn writing_a_save_replaces_it_whole() {
+    let map = Map::builtin();
+    let dir = common::temp_dir("write-to");
+    let path = dir.join("game.save.json");
+
+    save_with(&map, 10).write_to(&path).unwrap();
+    save_with(&map, 20).write_to(&path).unwrap();
+
+    assert_eq!(SaveFile::load(&path).unwrap().player.health, 20);
+
+    // nothing is left behind but the save itself
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+    assert_eq!(files.len(), 1);
+}
+
+#[test]
+fn a_write_cut_short_leaves_the_old_save() {
+    let map = Map::builtin();
+    let dir = common::temp_dir("write-to-crash");
+    let path = dir.join("game.save.json");
+
+    save_with(&map, 10).write_to(&path).unwrap();
+
+    // what a crash part way through the next write leaves
+    std::fs::write(path.with_extension("json.tmp"), "{\"format_ver").unwrap();
+
+    assert_eq!(SaveFile::load(&path).unwrap().player.health, 10);
+
+    save_with(&map, 20).write_to(&path).unwrap();
+
+    assert_eq!(SaveFile::load(&path).unwrap().player.health, 20);
+    assert!(!path.with_extension("json.tmp").exists());
+}
+
+#[test]
+fn saving_makes_the_save_directory() {
+    let dir = common::temp_dir("save-dir").join("nested").join("saves");
+    let saves = SaveManager::new(&dir);
+
+    let path = saves.save("game", &save_with(&Map::builtin(), 10)).unwrap();
+
+    assert_eq!(path, dir.join("game.save.json"));
+    assert_eq!(saves.load("game.save").unwrap().player.health, 10);
+}
+
+#[test]
+fn autosaves_rotate_through_their_slots() {
+    let map = Map::builtin();
+    let saves = SaveManager::new(common::temp_dir("autosave"));
+
+    for health in 1..=4 {
+        saves.autosave("game", &save_with(&map, health), 3).unwrap();
+    }
+
+    let healths: Vec<i32> = (1..=3)
+        .map(|slot| {
+            saves
+                .load(&backup_name("game", slot))
+                .unwrap()
+                .player
+                .health
+        })
+        .collect();
+
+    // newest first, with the oldest dropped
+    assert_eq!(healths, [4, 3, 2]);
+    assert!(!saves.path(&backup_name("game", 4)).exists());
+    assert!(!saves.path("game").exists());
+}
+
+#[test]
+fn deleting_backups_leaves_the_save_and_other_games() {
+    let map = Map::builtin();
+    let saves = SaveManager::new(common::temp_dir("delete-backups"));
+
+    saves.save("game", &save_with(&map, 10)).unwrap();
+
+    for name in ["game", "other"] {
+        for _ in 0..2 {
+            saves.autosave(name, &save_with(&map, 10), 3).unwrap();
+        }
+    }
+
+    saves.delete_backups("game", 3).unwrap();
+
+    assert!(saves.path("game").exists());
+    assert!(!saves.path(&backup_name("game", 1)).exists());
+    assert!(!saves.path(&backup_name("game", 2)).exists());
+    assert!(saves.path(&backup_name("other", 1)).exists());
+    assert!(saves.path(&backup_name("other", 2)).exists());
+
+    // with nothing left to delete, it's still fine
+    saves.delete_backups("game", 3).unwrap();
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use gext::engine::{Engine, EngineError, Event, Stats};
+use gext::map::Map;
+use gext::riddles::WrongAnswerPolicy;
+use gext::solve;
+use gext::structs::Class;
+use serde_json::{json, Value};
+
+fn guarded(name: &str, to: &str, enemy: Value) -> Value {
+    let mut door = common::door(name, to);
+    door["enemy"] = enemy;
+    door
+}
+
+/// A hall, a vault and a crypt in a row, each door guarded by a dummy that doesn't hit back,
+/// with rolls that never vary.
+fn dummies() -> Map {
+    let dummy = |name: &str| json!({"name": name, "health": 1000, "attack": 0, "defense": 10});
+
+    common::map(
+        &json!({
+            "start_room": "Hall",
+            "lose_condition": null,
+            "rules": {"variance": 0},
+            "rooms": {
+                "Hall": common::room("Hall", vec![guarded("Vault", "Vault", dummy("First"))]),
+                "Vault": common::room("Vault", vec![guarded("Crypt", "Crypt", dummy("Second"))]),
+                "Crypt": common::room("Crypt", vec![])
+            }
+        })
+        .to_string(),
+    )
+}
+
+/// The player's and enemy's stats at the start of the latest round.
+fn round(events: &[Event]) -> (Stats, Stats) {
+    events
+        .iter()
+        .rev()
+        .find_map(|e| match e {
+            Event::BattleRound { player, enemy } => Some((*player, *enemy)),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn start(class: &str) -> (Engine, Vec<Event>) {
+    let map = dummies();
+    let mut engine = common::engine(&map, common::class(class), 1);
+    let events = common::play(&mut engine, &["go Vault"]);
+
+    (engine, events)
+}
+
+#[test]
+fn strikes_hit_harder_and_run_out() {
+    let (mut engine, _) = start("Warrior");
+
+    // 40 attack against 10 defense, doubled
+    let events = common::play(&mut engine, &["cleave"]);
+    assert_eq!(round(&events).1.health, 1000 - 60);
+
+    let events = common::play(&mut engine, &["cleave"]);
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::Error(EngineError::UnknownAction))));
+
+    let events = common::play(&mut engine, &["attack"]);
+    assert_eq!(round(&events).1.health, 1000 - 60 - 30);
+}
+
+#[test]
+fn piercing_strikes_ignore_defense() {
+    let (mut engine, _) = start("Mage");
+
+    // 15 attack, with the dummy's 10 defense ignored
+    let events = common::play(&mut engine, &["fireball"]);
+    assert_eq!(round(&events).1.health, 1000 - 15);
+
+    let events = common::play(&mut engine, &["attack"]);
+    assert_eq!(round(&events).1.health, 1000 - 15 - 5);
+}
+
+#[test]
+fn heals_are_a_share_of_the_health_the_battle_started_with() {
+    let (mut engine, _) = start("Monk");
+
+    let events = common::play(&mut engine, &["meditate"]);
+
+    // a quarter of 175
+    assert!(events.iter().any(|e| matches!(e, Event::Healed(43))));
+    assert_eq!(round(&events).0.health, 175 + 43);
+}
+
+#[test]
+fn stuns_skip_the_enemy_s_turn() {
+    let (mut engine, _) = start("Rogue");
+    let stuns = |events: &[Event]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, Event::EnemyStunned))
+            .count()
+    };
+
+    let events = common::play(&mut engine, &["smoke bomb"]);
+    assert_eq!(stuns(&events), 1);
+
+    let events = common::play(&mut engine, &["attack"]);
+    assert_eq!(stuns(&events), 0);
+}
+
+#[test]
+fn skills_come_back_for_the_next_battle() {
+    let map = {
+        let mut map = dummies();
+        for room in map.rooms.values_mut() {
+            for door in &mut room.doors {
+                if let Some(enemy) = &mut door.enemy {
+                    enemy.health = 60;
+                }
+            }
+        }
+        map
+    };
+    let mut engine = common::engine(&map, common::class("Warrior"), 1);
+
+    common::play(&mut engine, &["go Vault", "cleave"]);
+    assert_eq!(engine.player().current_room, "Vault");
+
+    let events = common::play(&mut engine, &["go Crypt", "cleave"]);
+    assert!(!events.iter().any(|e| matches!(e, Event::Error(_))));
+    assert_eq!(engine.player().current_room, "Crypt");
+}
+
+#[test]
+fn the_solver_uses_skills() {
+    // without the staff, the Mage's 15 attack can't get through the guard's defense, but two
+    // fireballs can
+    let guard = json!({"name": "Guard", "health": 30, "attack": 10, "defense": 15});
+    let map = common::map(
+        &json!({
+            "start_room": "Hall",
+            "rules": {"variance": 0},
+            "rooms": {
+                "Hall": common::room("Hall", vec![guarded("Vault", "Vault", guard)]),
+                "Vault": common::room("Vault", vec![])
+            }
+        })
+        .to_string(),
+    );
+
+    let solve = |class: &Class| {
+        let policy = WrongAnswerPolicy::default();
+        solve::solve(&map.rooms, &map.start_room, class, &policy, &map.rules)
+    };
+
+    let mut mage = common::class("Mage");
+    mage.starting_items.clear();
+    assert!(solve(&mage).is_complete());
+
+    mage.skills.clear();
+    assert!(!solve(&mage).is_complete());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use gext::map::Map;
+use gext::riddles::WrongAnswerPolicy;
+use gext::solve::{solve, KeyLoss, Solution};
+use gext::structs::Key;
+use serde_json::{json, Value};
+
+fn locked(name: &str, key: Value) -> Value {
+    let mut door = common::door(name, name);
+    door["locked"] = json!(true);
+    door["key"] = key;
+    door
+}
+
+/// A hall with the single-use key `k` lying in it, and the given doors out.
+fn hall(doors: Vec<Value>, rooms: &[&str]) -> Map {
+    let mut hall = common::room("Hall", doors);
+    hall["keys"] = json!([{"name": "k", "single_use": true}]);
+
+    let mut all = json!({"Hall": hall});
+
+    for room in rooms {
+        all[room] = common::room(room, vec![]);
+    }
+
+    common::map(&json!({"start_room": "Hall", "rooms": all}).to_string())
+}
+
+fn solve_as_warrior(map: &Map, policy: &WrongAnswerPolicy) -> Solution {
+    solve(
+        &map.rooms,
+        &map.start_room,
+        &common::class("Warrior"),
+        policy,
+        &map.rules,
+    )
+}
+
+fn keeps_keys() -> WrongAnswerPolicy {
+    WrongAnswerPolicy {
+        despawn_keys: false,
+        ..WrongAnswerPolicy::default()
+    }
+}
+
+#[test]
+fn a_single_use_key_can_go_to_either_door() {
+    let map = hall(
+        vec![
+            locked("Closet", json!({"name": "k"})),
+            locked("Vault", json!({"name": "k"})),
+        ],
+        &["Closet", "Vault"],
+    );
+
+    let solution = solve_as_warrior(&map, &keeps_keys());
+
+    assert!(solution.is_complete());
+
+    let spent: Vec<String> = solution.soft_locks.iter().map(|s| s.to_string()).collect();
+
+    assert_eq!(
+        spent,
+        [
+            "spending the \"k\" key on the \"Closet\" door in the \"Hall\" cuts off \"Vault\"",
+            "spending the \"k\" key on the \"Vault\" door in the \"Hall\" cuts off \"Closet\"",
+        ]
+    );
+}
+
+#[test]
+fn spending_a_key_only_matters_if_nothing_else_fits() {
+    let map = hall(
+        vec![
+            locked("Closet", json!({"name": "k"})),
+            locked("Vault", json!({"any": [{"name": "k"}, {"name": "master"}]})),
+        ],
+        &["Closet", "Vault"],
+    );
+
+    let mut class = common::class("Warrior");
+    class.starting_keys.push(Key {
+        name: "master".to_string(),
+        single_use: false,
+        opens: vec![],
+    });
+
+    let solution = solve(
+        &map.rooms,
+        &map.start_room,
+        &class,
+        &keeps_keys(),
+        &map.rules,
+    );
+
+    assert!(solution.is_complete());
+    assert!(solution.soft_locks.is_empty(), "{:?}", solution.soft_locks);
+}
+
+#[test]
+fn spent_keys_and_riddle_losses_are_told_apart() {
+    let map = hall(vec![locked("Vault", json!({"name": "k"}))], &["Vault"]);
+
+    let solution = solve_as_warrior(&map, &WrongAnswerPolicy::default());
+
+    let losses: Vec<&KeyLoss> = solution.soft_locks.iter().map(|s| &s.loss).collect();
+
+    // the only door the key fits is the one it's needed for, so spending it there is fine
+    assert_eq!(losses, [&KeyLoss::Riddle]);
+}
+
+#[test]
+fn huge_items_and_winnings_stop_at_the_ends_of_i32() {
+    let giant = json!({
+        "name": "giant's potion", "description": "", "health": i32::MAX, "attack": i32::MAX,
+        "defense": i32::MAX
+    });
+    let mut hall = common::room("Hall", vec![]);
+    hall["items"] = json!([giant, giant]);
+    hall["doors"] = json!([{
+        "name": "Vault",
+        "description": "a heavy door",
+        "locked": false,
+        "enemy": {"name": "Giant", "health": 1_000_000, "attack": 1000, "defense": 0},
+        "associated_room_name": "Vault",
+        "requirements": null
+    }]);
+
+    let map = common::map(
+        &json!({
+            "start_room": "Hall",
+            "rooms": {"Hall": hall, "Vault": common::room("Vault", vec![])}
+        })
+        .to_string(),
+    );
+
+    assert!(solve_as_warrior(&map, &keeps_keys()).is_complete());
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use std::process::Command;
+
+use gext::content::validate_classes;
+use gext::map::Map;
+use gext::structs::{Ability, Class, Key, Lock};
+use gext::validate::{validate_map, Issue};
+use serde_json::{json, Value};
+
+fn map(header: Value, rooms: Value) -> Map {
+    let mut map = header;
+    map["rooms"] = rooms;
+    map["start_room"] = map.get("start_room").cloned().unwrap_or(json!("Hall"));
+
+    common::map(&map.to_string())
+}
+
+fn issues(map: &Map) -> Vec<Issue> {
+    validate_map(map, &Class::builtins())
+}
+
+#[test]
+fn a_sound_map_has_no_issues() {
+    let map = map(
+        json!({"win_condition": {"type": "reach_room", "room": "Vault"}}),
+        json!({"Hall": common::room("Hall", vec![common::door("Vault", "Vault")]), "Vault": common::room("Vault", vec![])}),
+    );
+
+    assert_eq!(issues(&map), []);
+}
+
+#[test]
+fn broken_rooms_are_errors() {
+    let map = map(
+        json!({"start_room": "Attic"}),
+        json!({
+            "Hall": common::room("Hall", vec![common::door("Cellar", "Cellar")]),
+            "Vault": common::room("Strongroom", vec![])
+        }),
+    );
+
+    let issues = issues(&map);
+
+    for issue in [
+        Issue::NameMismatch {
+            key: "Vault".to_string(),
+            name: "Strongroom".to_string(),
+        },
+        Issue::MissingStartRoom("Attic".to_string()),
+        Issue::DanglingDoor {
+            room: "Hall".to_string(),
+            door: "Cellar".to_string(),
+            target: "Cellar".to_string(),
+        },
+    ] {
+        assert!(issue.is_error());
+        assert!(issues.contains(&issue), "{:?} is missing", issue);
+    }
+}
+
+#[test]
+fn doors_nobody_can_use_are_warnings() {
+    let mut keyless = common::door("Cellar", "Cellar");
+    keyless["locked"] = json!(true);
+
+    let mut lost_key = common::door("Vault", "Vault");
+    lost_key["locked"] = json!(true);
+    lost_key["key"] = json!({"all": [{"name": "gold key"}, {"name": "skeleton key"}]});
+
+    let map = map(
+        json!({}),
+        json!({
+            "Hall": {
+                "name": "Hall",
+                "description": "a room",
+                "doors": [keyless, lost_key, common::door("Vault", "Vault")],
+                "items": [],
+                "keys": [{"name": "skeleton key"}]
+            },
+            "Cellar": common::room("Cellar", vec![]),
+            "Vault": common::room("Vault", vec![]),
+            "Attic": common::room("Attic", vec![])
+        }),
+    );
+
+    let issues = issues(&map);
+
+    for issue in [
+        Issue::LockedWithoutKey {
+            room: "Hall".to_string(),
+            door: "Cellar".to_string(),
+        },
+        Issue::UnobtainableKey {
+            room: "Hall".to_string(),
+            door: "Vault".to_string(),
+            lock: Lock::All {
+                all: vec![Key {
+                    name: "gold key".to_string(),
+                    single_use: false,
+                    opens: vec![],
+                }],
+            },
+        },
+        Issue::DuplicateDoor {
+            room: "Hall".to_string(),
+            door: "Vault".to_string(),
+        },
+        Issue::UnreachableRoom("Attic".to_string()),
+    ] {
+        assert!(!issue.is_error());
+        assert!(issues.contains(&issue), "{:?} is missing", issue);
+    }
+}
+
+#[test]
+fn win_conditions_must_be_in_the_map() {
+    for (condition, issue) in [
+        (
+            json!({"type": "reach_room", "room": "Throne Room"}),
+            Issue::UnknownWinRoom("Throne Room".to_string()),
+        ),
+        (
+            json!({"type": "hold_items", "items": ["crown"]}),
+            Issue::UnknownWinItem("crown".to_string()),
+        ),
+        (
+            json!({"type": "defeat_enemy", "enemy": "Dragon"}),
+            Issue::UnknownWinEnemy("Dragon".to_string()),
+        ),
+    ] {
+        let map = map(
+            json!({"win_condition": condition}),
+            json!({"Hall": common::room("Hall", vec![])}),
+        );
+
+        assert_eq!(issues(&map), [issue]);
+    }
+}
+
+#[test]
+fn damage_rules_out_of_range_are_errors() {
+    let map = map(
+        json!({"rules": {"variance": 101, "crit_chance": 101, "crit_multiplier": 99}}),
+        json!({"Hall": common::room("Hall", vec![])}),
+    );
+
+    let rules: Vec<&str> = issues(&map)
+        .into_iter()
+        .filter_map(|issue| match issue {
+            Issue::BadRule { rule, .. } => Some(rule),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rules, ["variance", "crit_chance", "crit_multiplier"]);
+}
+
+#[test]
+fn class_crits_are_held_to_the_same_range_as_the_map_s() {
+    let mut classes = Class::builtins();
+    classes[0].abilities = vec![Ability::Crit {
+        chance: 10,
+        multiplier: 99,
+    }];
+
+    assert!(validate_classes(&classes).is_err());
+
+    classes[0].abilities = vec![Ability::Crit {
+        chance: 10,
+        multiplier: 100,
+    }];
+
+    assert!(validate_classes(&classes).is_ok());
+}
+
+#[test]
+fn the_map_to_validate_can_come_after_flags() {
+    let dir = common::temp_dir("validate-args");
+    let path = dir.join("broken.map.json");
+
+    let broken = map(
+        json!({"start_room": "Attic"}),
+        json!({"Hall": common::room("Hall", vec![])}),
+    );
+    std::fs::write(&path, serde_json::to_string(&broken).unwrap()).unwrap();
+
+    let classes = dir.join("one.classes.json");
+    std::fs::write(
+        &classes,
+        serde_json::to_string(&Class::builtins()[..1]).unwrap(),
+    )
+    .unwrap();
+
+    for args in [
+        vec!["validate".as_ref(), path.as_os_str()],
+        vec![
+            "validate".as_ref(),
+            "-c".as_ref(),
+            classes.as_os_str(),
+            path.as_os_str(),
+        ],
+        vec!["validate".as_ref(), "--debug".as_ref(), path.as_os_str()],
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_gext"))
+            .args(args)
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(stdout.contains("\"Attic\" is not in the map"), "{}", stdout);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use gext::map::Map;
+use gext::save::{DoorRef, Placed, WorldChanges};
+use gext::structs::{Door, Room};
+use serde_json::Value;
+
+fn door<'a>(rooms: &'a mut HashMap<String, Room>, room: &str, door: &str) -> &'a mut Door {
+    rooms
+        .get_mut(room)
+        .unwrap()
+        .doors
+        .iter_mut()
+        .find(|d| d.name == door)
+        .unwrap()
+}
+
+fn placed(list: &[Placed]) -> Vec<(&str, &str)> {
+    list.iter()
+        .map(|p| (p.room.as_str(), p.pickup.name()))
+        .collect()
+}
+
+fn doors(list: &[DoorRef]) -> Vec<(&str, &str)> {
+    list.iter()
+        .map(|d| (d.room.as_str(), d.door.as_str()))
+        .collect()
+}
+
+/// Rooms as JSON, which compares them regardless of the order they're stored in.
+fn json(rooms: &HashMap<String, Room>) -> Value {
+    serde_json::to_value(rooms).unwrap()
+}
+
+/// The built-in map after a bit of play: the potion carried to the Armory, the kitchen key taken,
+/// the Kitchen unlocked, the Armory locked, the Stage unlocked and locked again, and the stressed
+/// actor beaten.
+fn played() -> (HashMap<String, Room>, HashMap<String, Room>) {
+    let pristine = Map::builtin().rooms;
+    let mut played = pristine.clone();
+
+    let hall = played.get_mut("Entrance Hall").unwrap();
+    let potion = hall.items.remove(0);
+    hall.keys.retain(|k| k.name != "kitchen");
+    played.get_mut("Armory").unwrap().items.push(potion);
+
+    door(&mut played, "Entrance Hall", "Kitchen").locked = false;
+    door(&mut played, "Entrance Hall", "Armory").locked = true;
+
+    door(&mut played, "Concert Hall", "Stage").locked = false;
+    door(&mut played, "Concert Hall", "Stage").locked = true;
+
+    door(&mut played, "Backstage", "Dressing Room")
+        .enemy
+        .as_mut()
+        .unwrap()
+        .take_loot();
+
+    (pristine, played)
+}
+
+#[test]
+fn changes_list_what_happened() {
+    let (pristine, played) = played();
+    let changes = WorldChanges::between(&pristine, &played);
+
+    assert_eq!(
+        placed(&changes.removed),
+        [("Entrance Hall", "potion"), ("Entrance Hall", "kitchen")]
+    );
+    assert_eq!(placed(&changes.added), [("Armory", "potion")]);
+    assert_eq!(doors(&changes.unlocked), [("Entrance Hall", "Kitchen")]);
+    assert_eq!(doors(&changes.locked), [("Entrance Hall", "Armory")]);
+    assert_eq!(doors(&changes.looted), [("Backstage", "Dressing Room")]);
+}
+
+#[test]
+fn changes_rebuild_the_world_they_came_from() {
+    let (pristine, played) = played();
+
+    let changes = WorldChanges::between(&pristine, &played);
+
+    // through JSON, as in a save
+    let changes: WorldChanges =
+        serde_json::from_str(&serde_json::to_string(&changes).unwrap()).unwrap();
+
+    assert_eq!(json(&changes.apply(&pristine).unwrap()), json(&played));
+}
+
+#[test]
+fn an_unchanged_world_has_no_changes() {
+    let pristine = Map::builtin().rooms;
+    let changes = WorldChanges::between(&pristine, &pristine);
+
+    assert_eq!(serde_json::to_string(&changes).unwrap(), "{}");
+    assert_eq!(json(&changes.apply(&pristine).unwrap()), json(&pristine));
+}
+
+#[test]
+fn changes_that_dont_fit_the_map_are_refused() {
+    let (pristine, played) = played();
+    let changes = WorldChanges::between(&pristine, &played);
+
+    let mut other = pristine.clone();
+    other.get_mut("Entrance Hall").unwrap().items.clear();
+
+    assert!(changes.apply(&other).is_none());
+}